@@ -0,0 +1,215 @@
+//! Backups to S3-compatible storage: export and import services.
+//! Export and import are long-running operations, use [`crate::operation::OperationClient`] to wait them or to watch progress
+//!
+//! # Examples
+//! ```rust,no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//!     use futures::StreamExt;
+//!     use ydb_unofficial::backup::{S3Storage, S3Item, S3ExportSettings, export_progress};
+//!     use ydb_unofficial::operation::WaitPolicy;
+//!     let mut conn = ydb_unofficial::YdbConnection::from_env();
+//!     let storage = S3Storage {
+//!         endpoint: "storage.yandexcloud.net".to_owned(),
+//!         bucket: "backups".to_owned(),
+//!         access_key: "key".to_owned(),
+//!         secret_key: "secret".to_owned(),
+//!         ..Default::default()
+//!     };
+//!     let items = vec![S3Item::new("/local/users", "2023-07-01/users")];
+//!     let operation = conn.export_to_s3(S3ExportSettings { storage, items, ..Default::default() }).await.unwrap();
+//!     let mut progress = std::pin::pin!(export_progress(conn.operation().watch(operation, WaitPolicy::default())));
+//!     while let Some(metadata) = progress.next().await {
+//!         println!("{:?}", metadata.unwrap().progress());
+//!     }
+//! # }
+//! ```
+use futures::{Stream, StreamExt};
+
+use super::*;
+use auth::Credentials;
+use client::{delegate, YdbConnection};
+use error::YdbError;
+use payload::ExtractResultError;
+use operation::{async_params, started};
+
+use generated::ydb::operations::Operation;
+use generated::ydb::export::v1::export_service_client::ExportServiceClient;
+use generated::ydb::export::*;
+use generated::ydb::import::v1::import_service_client::ImportServiceClient;
+use generated::ydb::import::*;
+
+pub use generated::ydb::export::export_to_s3_settings::StorageClass;
+pub use generated::ydb::export::ExportToS3Metadata;
+pub use generated::ydb::import::ImportFromS3Metadata;
+
+/// Location and credentials of S3-compatible storage
+#[derive(Debug, Clone, Default)]
+pub struct S3Storage {
+    /// Host (and port) of storage without scheme, e.g. `storage.yandexcloud.net`
+    pub endpoint: String,
+    pub bucket: String,
+    pub access_key: String,
+    pub secret_key: String,
+    pub region: String,
+    /// Use http instead of https (e.g. for local S3 stand-in)
+    pub insecure: bool,
+}
+
+/// Table (or directory) of database and its prefix in bucket
+#[derive(Debug, Clone)]
+pub struct S3Item {
+    pub path: String,
+    pub prefix: String,
+}
+
+impl S3Item {
+    pub fn new(path: &str, prefix: &str) -> Self {
+        Self { path: path.to_owned(), prefix: prefix.to_owned() }
+    }
+}
+
+/// Settings of export to S3
+#[derive(Debug, Clone, Default)]
+pub struct S3ExportSettings {
+    pub storage: S3Storage,
+    pub items: Vec<S3Item>,
+    pub description: String,
+    pub number_of_retries: u32,
+    pub storage_class: StorageClass,
+    /// Codec of exported data, e.g. `zstd` or `zstd-3` (no compression if empty)
+    pub compression: String,
+}
+
+/// Settings of import from S3
+#[derive(Debug, Clone, Default)]
+pub struct S3ImportSettings {
+    pub storage: S3Storage,
+    pub items: Vec<S3Item>,
+    pub description: String,
+    pub number_of_retries: u32,
+}
+
+impl From<S3ExportSettings> for ExportToS3Settings {
+    fn from(value: S3ExportSettings) -> Self {
+        use export_to_s3_settings::{Item, Scheme};
+        let S3ExportSettings { storage, items, description, number_of_retries, storage_class, compression } = value;
+        let scheme = if storage.insecure { Scheme::Http } else { Scheme::Https };
+        Self {
+            endpoint: storage.endpoint,
+            scheme: scheme.into(),
+            bucket: storage.bucket,
+            access_key: storage.access_key,
+            secret_key: storage.secret_key,
+            items: items.into_iter().map(|i| Item { source_path: i.path, destination_prefix: i.prefix }).collect(),
+            description,
+            number_of_retries,
+            storage_class: storage_class.into(),
+            compression,
+            region: storage.region,
+        }
+    }
+}
+
+impl From<S3ImportSettings> for ImportFromS3Settings {
+    fn from(value: S3ImportSettings) -> Self {
+        use import_from_s3_settings::{Item, Scheme};
+        let S3ImportSettings { storage, items, description, number_of_retries } = value;
+        let scheme = if storage.insecure { Scheme::Http } else { Scheme::Https };
+        Self {
+            endpoint: storage.endpoint,
+            scheme: scheme.into(),
+            bucket: storage.bucket,
+            access_key: storage.access_key,
+            secret_key: storage.secret_key,
+            items: items.into_iter().map(|i| Item { source_prefix: i.prefix, destination_path: i.path }).collect(),
+            description,
+            number_of_retries,
+        }
+    }
+}
+
+/// [`ExportServiceClient`] wrapper, that checks status of each response.
+/// Use [`YdbConnection::export`] to create it
+#[derive(Debug)]
+pub struct ExportClient<'a, C: Credentials> {
+    client: ExportServiceClient<&'a mut YdbConnection<C>>,
+}
+
+impl<'a, C: Credentials> ExportClient<'a, C> {
+    pub(crate) fn new(conn: &'a mut YdbConnection<C>) -> Self {
+        Self { client: ExportServiceClient::new(conn) }
+    }
+    delegate!{
+        fn export_to_yt(ExportToYtRequest) -> ExportToYtResponse;
+        fn export_to_s3(ExportToS3Request) -> ExportToS3Response;
+    }
+    /// Starts export to S3. Returns long-running operation, see [`export_progress`]
+    pub async fn to_s3(&mut self, settings: S3ExportSettings) -> Result<Operation, YdbError> {
+        let req = ExportToS3Request { operation_params: Some(async_params()), settings: Some(settings.into()) };
+        let response = started(self.export_to_s3(req).await)?;
+        response.operation.ok_or(YdbError::EmptyResponse)
+    }
+}
+
+/// [`ImportServiceClient`] wrapper, that checks status of each response.
+/// Use [`YdbConnection::import`] to create it
+#[derive(Debug)]
+pub struct ImportClient<'a, C: Credentials> {
+    client: ImportServiceClient<&'a mut YdbConnection<C>>,
+}
+
+impl<'a, C: Credentials> ImportClient<'a, C> {
+    pub(crate) fn new(conn: &'a mut YdbConnection<C>) -> Self {
+        Self { client: ImportServiceClient::new(conn) }
+    }
+    delegate!{
+        fn import_from_s3(ImportFromS3Request) -> ImportFromS3Response;
+        fn import_data(ImportDataRequest) -> ImportDataResponse;
+    }
+    /// Starts import from S3. Tables must not exist. Returns long-running operation, see [`import_progress`]
+    pub async fn from_s3(&mut self, settings: S3ImportSettings) -> Result<Operation, YdbError> {
+        let req = ImportFromS3Request { operation_params: Some(async_params()), settings: Some(settings.into()) };
+        let response = started(self.import_from_s3(req).await)?;
+        response.operation.ok_or(YdbError::EmptyResponse)
+    }
+}
+
+fn metadata<T: prost::Message + Default>(operation: &Operation) -> Result<T, ExtractResultError> {
+    match &operation.metadata {
+        Some(metadata) => Ok(T::decode(metadata.value.as_slice())?),
+        None => Err(ExtractResultError::Empty),
+    }
+}
+
+/// Decodes metadata of export operation
+pub fn export_metadata(operation: &Operation) -> Result<ExportToS3Metadata, ExtractResultError> {
+    metadata(operation)
+}
+
+/// Decodes metadata of import operation
+pub fn import_metadata(operation: &Operation) -> Result<ImportFromS3Metadata, ExtractResultError> {
+    metadata(operation)
+}
+
+/// Converts stream of operation states (see [`crate::operation::OperationClient::watch`]) to stream of export progress
+pub fn export_progress<'a>(operations: impl Stream<Item = Result<Operation, YdbError>> + 'a) -> impl Stream<Item = Result<ExportToS3Metadata, YdbError>> + 'a {
+    operations.map(|operation| Ok(export_metadata(&operation?)?))
+}
+
+/// Converts stream of operation states (see [`crate::operation::OperationClient::watch`]) to stream of import progress
+pub fn import_progress<'a>(operations: impl Stream<Item = Result<Operation, YdbError>> + 'a) -> impl Stream<Item = Result<ImportFromS3Metadata, YdbError>> + 'a {
+    operations.map(|operation| Ok(import_metadata(&operation?)?))
+}
+
+#[test]
+fn s3_settings() {
+    let storage = S3Storage { endpoint: "localhost:9000".to_owned(), bucket: "b".to_owned(), insecure: true, ..Default::default() };
+    let items = vec![S3Item::new("/local/t", "backup/t")];
+    let export = ExportToS3Settings::from(S3ExportSettings { storage: storage.clone(), items: items.clone(), ..Default::default() });
+    assert_eq!(export.scheme(), export_to_s3_settings::Scheme::Http);
+    assert_eq!((export.items[0].source_path.as_str(), export.items[0].destination_prefix.as_str()), ("/local/t", "backup/t"));
+    let import = ImportFromS3Settings::from(S3ImportSettings { storage, items, ..Default::default() });
+    assert_eq!(import.scheme(), import_from_s3_settings::Scheme::Http);
+    assert_eq!((import.items[0].source_prefix.as_str(), import.items[0].destination_path.as_str()), ("backup/t", "/local/t"));
+}
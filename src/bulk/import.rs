@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+use std::iter::Peekable;
+
+use csv::{ByteRecord, ReaderBuilder, StringRecord};
+use serde_json::{Map, Value as Json};
+
+use crate::auth::Credentials;
+use crate::client::{YdbConnection, YdbService};
+use crate::error::YdbError;
+use crate::payload::YdbResponseWithResult;
+use crate::generated::ydb::{Column, Type, Value};
+use crate::generated::ydb::formats::CsvSettings;
+use crate::generated::ydb::table::{BulkUpsertRequest, DescribeTableRequest};
+use crate::generated::ydb::table::bulk_upsert_request::DataFormat;
+use super::parse::{parse_value, is_optional};
+use super::{upsert_all, rows_value};
+
+/// Settings of [`TableImporter`]
+#[derive(Debug, Clone)]
+pub struct ImportOptions {
+    /// Max rows in one `BulkUpsert` request
+    pub batch_rows: usize,
+    /// Count of `BulkUpsert` requests in parallel
+    pub concurrency: usize,
+    /// Delimiter of CSV fields
+    pub delimiter: u8,
+    /// CSV field with this text is NULL (for optional columns only)
+    pub null_value: String,
+    /// Send CSV as is and let server parse it. It is faster, but input must be valid CSV in format of server
+    pub server_side: bool,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self { batch_rows: 10_000, concurrency: 4, delimiter: b',', null_value: String::new(), server_side: false }
+    }
+}
+
+fn invalid(message: impl ToString) -> YdbError {
+    YdbError::InvalidInput(message.to_string())
+}
+
+type Objects<'a> = Peekable<Box<dyn Iterator<Item = Result<(usize, Map<String, Json>), YdbError>> + Send + 'a>>;
+
+/// Loads CSV or JSON-lines data into table. Values are converted to types of columns (by schema of table)
+/// and sent with parallel `BulkUpsert` requests. Rows of one input may be loaded partially, if error occurs
+#[derive(Debug, Clone)]
+pub struct TableImporter<C: Credentials> {
+    service: YdbService<C>,
+    table: String,
+    types: HashMap<String, Type>,
+    options: ImportOptions,
+}
+
+impl<C: Credentials> TableImporter<C> {
+    /// Describes table to know types of its columns
+    pub async fn new(conn: &mut YdbConnection<C>, table: &str, options: ImportOptions) -> Result<Self, YdbError> {
+        let req = DescribeTableRequest { path: table.to_owned(), ..Default::default() };
+        let scheme = conn.table().await?.describe_table(req).await?.into_inner().result()?;
+        let types = scheme.columns.into_iter().filter_map(|c| Some((c.name, c.r#type?))).collect();
+        Ok(Self { service: conn.service(), table: table.to_owned(), types, options })
+    }
+    fn column(&self, name: &str) -> Result<Column, YdbError> {
+        match self.types.get(name) {
+            Some(ty) => Ok(Column { name: name.to_owned(), r#type: Some(ty.clone()) }),
+            None => Err(invalid(format!("table {} has no column {name}", self.table))),
+        }
+    }
+    fn request(&self, columns: &[Column], rows: Vec<Value>) -> (usize, BulkUpsertRequest) {
+        let count = rows.len();
+        (count, BulkUpsertRequest { table: self.table.clone(), rows: Some(rows_value(columns, rows)), ..Default::default() })
+    }
+    /// Loads CSV with header (names of columns). Returns count of loaded rows
+    pub async fn csv(&self, data: &[u8]) -> Result<usize, YdbError> {
+        if self.options.server_side {
+            let chunks = csv_chunks(data, self.options.delimiter, self.options.batch_rows)?;
+            let requests = chunks.map(|chunk| chunk.map(|(rows, data)| (rows, self.csv_request(data))));
+            return upsert_all(self.service.clone(), requests, self.options.concurrency).await;
+        }
+        let mut reader = ReaderBuilder::new().delimiter(self.options.delimiter).from_reader(data);
+        let columns = reader.headers().map_err(invalid)?.iter().map(|name| self.column(name)).collect::<Result<Vec<_>, _>>()?;
+        let mut records = reader.into_records();
+        let requests = std::iter::from_fn(|| {
+            let rows = records.by_ref()
+                .take(self.options.batch_rows.max(1))
+                .map(|record| self.csv_row(&columns, &record.map_err(invalid)?))
+                .collect::<Result<Vec<_>, _>>();
+            match rows {
+                Ok(rows) if rows.is_empty() => None,
+                Ok(rows) => Some(Ok(self.request(&columns, rows))),
+                Err(e) => Some(Err(e)),
+            }
+        });
+        upsert_all(self.service.clone(), requests, self.options.concurrency).await
+    }
+    fn csv_request(&self, data: Vec<u8>) -> BulkUpsertRequest {
+        let settings = CsvSettings {
+            delimiter: vec![self.options.delimiter],
+            null_value: self.options.null_value.clone().into_bytes(),
+            header: true,
+            ..Default::default()
+        };
+        BulkUpsertRequest { table: self.table.clone(), data, data_format: Some(DataFormat::CsvSettings(settings)), ..Default::default() }
+    }
+    fn csv_row(&self, columns: &[Column], record: &StringRecord) -> Result<Value, YdbError> {
+        let line = record.position().map_or(0, |p| p.line());
+        let items = columns.iter().zip(record.iter()).map(|(column, field)| {
+            let ty = column.r#type.as_ref().expect("type of column is set");
+            let text = (field != self.options.null_value || !is_optional(ty)).then_some(field);
+            parse_value(text, ty).map_err(|e| invalid(format!("line {line}, column {}: {e}", column.name)))
+        }).collect::<Result<_, _>>()?;
+        Ok(Value { items, ..Default::default() })
+    }
+    /// Loads JSON objects, one per line. Consecutive lines with the same set of keys are sent in one request,
+    /// absent keys are not changed in table. Returns count of loaded rows
+    pub async fn json_lines(&self, data: &[u8]) -> Result<usize, YdbError> {
+        let objects = data.split(|b| *b == b'\n')
+            .enumerate()
+            .filter(|(_, line)| !line.iter().all(u8::is_ascii_whitespace))
+            .map(|(n, line)| match serde_json::from_slice(line) {
+                Ok(Json::Object(object)) => Ok((n + 1, object)),
+                Ok(_) => Err(invalid(format!("line {}: object expected", n + 1))),
+                Err(e) => Err(invalid(format!("line {}: {e}", n + 1))),
+            });
+        let mut objects: Objects = (Box::new(objects) as Box<dyn Iterator<Item = _> + Send>).peekable();
+        let requests = std::iter::from_fn(|| {
+            let first = match objects.next()? {
+                Ok(first) => first,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(self.json_batch(first, &mut objects))
+        });
+        upsert_all(self.service.clone(), requests, self.options.concurrency).await
+    }
+    /// Makes request of `first` object and next objects with the same keys
+    fn json_batch(&self, first: (usize, Map<String, Json>), objects: &mut Objects) -> Result<(usize, BulkUpsertRequest), YdbError> {
+        let columns = first.1.keys().map(|name| self.column(name)).collect::<Result<Vec<_>, _>>()?;
+        let mut rows = Vec::new();
+        let mut next = Some(first);
+        while let Some((line, object)) = next.take() {
+            rows.push(self.json_row(&columns, line, object)?);
+            let same_keys = |object: &Map<String, Json>| object.keys().eq(columns.iter().map(|c| &c.name));
+            if rows.len() < self.options.batch_rows && matches!(objects.peek(), Some(Ok((_, object))) if same_keys(object)) {
+                next = objects.next().and_then(Result::ok);
+            }
+        }
+        Ok(self.request(&columns, rows))
+    }
+    fn json_row(&self, columns: &[Column], line: usize, mut object: Map<String, Json>) -> Result<Value, YdbError> {
+        let items = columns.iter().map(|column| {
+            let ty = column.r#type.as_ref().expect("type of column is set");
+            let text = match object.remove(&column.name) {
+                Some(Json::Null) | None => None,
+                Some(Json::String(s)) => Some(s),
+                Some(json) => Some(json.to_string()),
+            };
+            parse_value(text.as_deref(), ty).map_err(|e| invalid(format!("line {line}, column {}: {e}", column.name)))
+        }).collect::<Result<_, _>>()?;
+        Ok(Value { items, ..Default::default() })
+    }
+}
+
+/// Splits CSV to chunks with `rows` records (and count of records). Each chunk starts with header of `data`
+fn csv_chunks(data: &[u8], delimiter: u8, rows: usize) -> Result<impl Iterator<Item = Result<(usize, Vec<u8>), YdbError>> + '_, YdbError> {
+    let mut reader = ReaderBuilder::new().delimiter(delimiter).from_reader(data);
+    reader.byte_headers().map_err(invalid)?;
+    let header = &data[..reader.position().byte() as usize];
+    let mut record = ByteRecord::new();
+    Ok(std::iter::from_fn(move || {
+        let start = reader.position().byte() as usize;
+        let mut count = 0;
+        while count < rows.max(1) {
+            match reader.read_byte_record(&mut record) {
+                Ok(true) => count += 1,
+                Ok(false) => break,
+                Err(e) => return Some(Err(invalid(e))),
+            }
+        }
+        let end = reader.position().byte() as usize;
+        (count > 0).then(|| Ok((count, [header, &data[start..end]].concat())))
+    }))
+}
+
+/// Loads CSV with header into table, see [`TableImporter::csv`]
+pub async fn import_csv<C: Credentials>(conn: &mut YdbConnection<C>, table: &str, data: &[u8], options: ImportOptions) -> Result<usize, YdbError> {
+    TableImporter::new(conn, table, options).await?.csv(data).await
+}
+
+/// Loads JSON-lines into table, see [`TableImporter::json_lines`]
+pub async fn import_json_lines<C: Credentials>(conn: &mut YdbConnection<C>, table: &str, data: &[u8], options: ImportOptions) -> Result<usize, YdbError> {
+    TableImporter::new(conn, table, options).await?.json_lines(data).await
+}
+
+#[test]
+fn csv_chunks_with_header() {
+    let data = b"id,name\n1,\"a\nb\"\n2,c\n3,d";
+    let chunks = csv_chunks(data, b',', 2).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(chunks, vec![
+        (2, b"id,name\n1,\"a\nb\"\n2,c\n".to_vec()),
+        (1, b"id,name\n3,d".to_vec()),
+    ]);
+    assert_eq!(csv_chunks(b"id,name\n", b',', 2).unwrap().count(), 0);
+}
@@ -0,0 +1,104 @@
+//! Bulk loading of rows into tables with `BulkUpsert` requests: typed rows (see [`Row`] and
+//! [`crate::client::TableClientWithSession::bulk_upsert`]) and, with feature `bulk-import`, CSV and JSON-lines data
+//!
+//! # Examples
+//! ```rust,no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//!     use ydb_unofficial::bulk::Row;
+//!     let mut conn = ydb_unofficial::YdbConnection::from_env();
+//!     let rows = (1..=3u64).map(|id| Row::new().set("id", id).set("name", format!("user{id}")));
+//!     conn.table().await.unwrap().bulk_upsert("users", rows).await.unwrap();
+//! # }
+//! ```
+//! ```rust,no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//! # #[cfg(feature = "bulk-import")] {
+//!     use ydb_unofficial::bulk::{import_csv, import_json_lines, ImportOptions};
+//!     let mut conn = ydb_unofficial::YdbConnection::from_env();
+//!     let csv = b"id,name,created\n1,alice,2023-07-01T10:00:00Z\n2,bob,\n";
+//!     let rows = import_csv(&mut conn, "users", csv, ImportOptions::default()).await.unwrap();
+//!     assert_eq!(rows, 2);
+//!     let json = br#"{"id":3,"name":"carol","created":null}"#;
+//!     import_json_lines(&mut conn, "users", json, ImportOptions::default()).await.unwrap();
+//! # }
+//! # }
+//! ```
+mod rows;
+#[cfg(feature = "bulk-import")]
+mod parse;
+#[cfg(feature = "bulk-import")]
+mod import;
+
+pub use rows::*;
+#[cfg(feature = "bulk-import")]
+pub use import::*;
+
+use futures::TryStreamExt;
+
+use super::*;
+use auth::Credentials;
+use client::YdbService;
+use error::{YdbError, check_status};
+
+use generated::ydb::{TypedValue, Type, Value, StructType, StructMember, ListType};
+use generated::ydb::table::BulkUpsertRequest;
+use generated::ydb::table::v1::table_service_client::TableServiceClient;
+
+async fn upsert<C: Credentials>(mut client: TableServiceClient<YdbService<C>>, req: BulkUpsertRequest) -> Result<(), YdbError> {
+    let response = client.bulk_upsert(req).await?;
+    let operation = response.into_inner().operation.ok_or(YdbError::EmptyResponse)?;
+    check_status(operation.status, operation.issues)
+}
+
+/// Upserts rows without session, see [`crate::client::TableClientWithSession::bulk_upsert`]
+#[cfg(feature = "sqlx")]
+pub(crate) async fn upsert_rows<C: Credentials>(service: YdbService<C>, table: &str, rows: impl IntoIterator<Item = Row>) -> Result<(), YdbError> {
+    let req = BulkUpsertRequest { table: table.to_owned(), rows: Some(rows_list(rows)?), ..Default::default() };
+    upsert(TableServiceClient::new(service), req).await
+}
+
+/// Sends requests (with counts of their rows) with `concurrency` requests in parallel.
+/// Stops on first error. Returns count of upserted rows
+pub(crate) async fn upsert_all<C: Credentials>(
+    service: YdbService<C>,
+    requests: impl Iterator<Item = Result<(usize, BulkUpsertRequest), YdbError>>,
+    concurrency: usize,
+) -> Result<usize, YdbError> {
+    let client = TableServiceClient::new(service);
+    futures::stream::iter(requests)
+        .map_ok(|(rows, req)| {
+            let client = client.clone();
+            async move { upsert(client, req).await.map(|_| rows) }
+        })
+        .try_buffer_unordered(concurrency.max(1))
+        .try_fold(0, |total, rows| async move { Ok(total + rows) })
+        .await
+}
+
+/// Converts rows of result set to `List<Struct<...>>` value, that can be passed to `BulkUpsert`
+pub fn rows_value(columns: &[generated::ydb::Column], rows: Vec<Value>) -> TypedValue {
+    let members = columns.iter().map(|c| StructMember { name: c.name.clone(), r#type: c.r#type.clone() }).collect();
+    struct_list(members, rows)
+}
+
+/// `List<Struct<...>>` value of rows with struct `members`
+pub(crate) fn struct_list(members: Vec<StructMember>, rows: Vec<Value>) -> TypedValue {
+    use generated::ydb::r#type::Type as T;
+    let row_type = Type { r#type: Some(T::StructType(StructType { members })) };
+    let list_type = Type { r#type: Some(T::ListType(Box::new(ListType { item: Some(Box::new(row_type)) }))) };
+    TypedValue { r#type: Some(list_type), value: Some(Value { items: rows, ..Default::default() }) }
+}
+
+#[test]
+fn rows_as_list_of_structs() {
+    use generated::ydb::{Column, r#type::{Type as T, PrimitiveTypeId}, value::Value as V};
+    let column = Column { name: "id".to_owned(), r#type: Some(Type { r#type: Some(T::TypeId(PrimitiveTypeId::Uint64.into())) }) };
+    let row = Value { items: vec![Value { value: Some(V::Uint64Value(1)), ..Default::default() }], ..Default::default() };
+    let value = rows_value(std::slice::from_ref(&column), vec![row.clone()]);
+    let Some(T::ListType(list)) = value.r#type.and_then(|t| t.r#type) else { panic!("list expected") };
+    let Some(T::StructType(row_type)) = list.item.and_then(|t| t.r#type) else { panic!("struct expected") };
+    assert_eq!(row_type.members, vec![StructMember { name: column.name, r#type: column.r#type }]);
+    assert_eq!(value.value.unwrap().items, vec![row]);
+}
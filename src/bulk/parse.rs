@@ -0,0 +1,161 @@
+//! Conversion of text values (from CSV or JSON) to values of YDB by type of column
+use crate::generated::ydb::{Type, Value};
+use crate::generated::ydb::r#type::{Type as T, PrimitiveTypeId as P};
+use crate::generated::ydb::value::Value as V;
+
+const DAY_MICROS: i64 = 86_400_000_000;
+
+fn value(value: V) -> Value {
+    Value { value: Some(value), ..Default::default() }
+}
+
+pub(crate) fn is_optional(ty: &Type) -> bool {
+    matches!(ty.r#type, Some(T::OptionalType(_)))
+}
+
+/// Converts text to value of type `ty`. `None` means NULL, it is allowed for optional types only
+pub(crate) fn parse_value(text: Option<&str>, ty: &Type) -> Result<Value, String> {
+    match (&ty.r#type, text) {
+        (Some(T::OptionalType(_)), None) => Ok(value(V::NullFlagValue(0))),
+        (Some(T::OptionalType(optional)), Some(text)) => match optional.item.as_deref() {
+            Some(item) => parse_value(Some(text), item),
+            None => Err("optional type without item".to_owned()),
+        },
+        (_, None) => Err("NULL for not optional type".to_owned()),
+        (Some(T::TypeId(id)), Some(text)) => {
+            let id = P::from_i32(*id).ok_or_else(|| format!("unknown type id {id}"))?;
+            primitive(text, id).map(value)
+        }
+        (Some(T::DecimalType(decimal_type)), Some(text)) => decimal(text, decimal_type.scale),
+        (ty, _) => Err(format!("type {ty:?} is not supported")),
+    }
+}
+
+fn number<N: std::str::FromStr>(text: &str) -> Result<N, String> {
+    text.trim().parse().map_err(|_| format!("invalid number {text:?}"))
+}
+
+fn primitive(text: &str, id: P) -> Result<V, String> {
+    Ok(match id {
+        P::Bool => match text.trim() {
+            t if t == "1" || t.eq_ignore_ascii_case("true") => V::BoolValue(true),
+            t if t == "0" || t.eq_ignore_ascii_case("false") => V::BoolValue(false),
+            _ => return Err(format!("invalid bool {text:?}")),
+        },
+        P::Int8 => V::Int32Value(number::<i8>(text)?.into()),
+        P::Int16 => V::Int32Value(number::<i16>(text)?.into()),
+        P::Int32 => V::Int32Value(number(text)?),
+        P::Uint8 => V::Uint32Value(number::<u8>(text)?.into()),
+        P::Uint16 => V::Uint32Value(number::<u16>(text)?.into()),
+        P::Uint32 => V::Uint32Value(number(text)?),
+        P::Int64 | P::Interval => V::Int64Value(number(text)?),
+        P::Uint64 => V::Uint64Value(number(text)?),
+        P::Float => V::FloatValue(number(text)?),
+        P::Double => V::DoubleValue(number(text)?),
+        P::Date => V::Uint32Value(number(text).or_else(|_| {
+            let days = micros(text)?.div_euclid(DAY_MICROS);
+            days.try_into().map_err(|_| format!("date {text:?} is out of range"))
+        })?),
+        P::Datetime => V::Uint32Value(number(text).or_else(|_| {
+            let seconds = micros(text)?.div_euclid(1_000_000);
+            seconds.try_into().map_err(|_| format!("datetime {text:?} is out of range"))
+        })?),
+        P::Timestamp => V::Uint64Value(number(text).or_else(|_| {
+            micros(text)?.try_into().map_err(|_| format!("timestamp {text:?} is out of range"))
+        })?),
+        P::String | P::Yson => V::BytesValue(text.as_bytes().to_vec()),
+        P::Utf8 | P::Json | P::JsonDocument | P::Dynumber | P::TzDate | P::TzDatetime | P::TzTimestamp => V::TextValue(text.to_owned()),
+        id => return Err(format!("type {} is not supported", id.as_str_name())),
+    })
+}
+
+/// Decimal is sent as 128-bit integer, scaled by `10^scale`
+fn decimal(text: &str, scale: u32) -> Result<Value, String> {
+    let text = text.trim();
+    let (int, fraction) = text.split_once('.').unwrap_or((text, ""));
+    let scale = scale as usize;
+    if fraction.len() > scale || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format!("invalid decimal {text:?}"));
+    }
+    let scaled: i128 = format!("{int}{fraction:0<scale$}").parse().map_err(|_| format!("invalid decimal {text:?}"))?;
+    Ok(Value { high_128: (scaled >> 64) as u64, ..value(V::Low128(scaled as u64)) })
+}
+
+/// Days since unix epoch of date in proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month as i64 + 9) % 12) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn fields<const N: usize>(text: &str, separator: char) -> Option<[u32; N]> {
+    let mut parts = text.split(separator);
+    let mut result = [0; N];
+    for field in result.iter_mut() {
+        let part = parts.next()?;
+        if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        *field = part.parse().ok()?;
+    }
+    parts.next().is_none().then_some(result)
+}
+
+/// Parses `YYYY-MM-DD[(T| )HH:MM:SS[.ffffff]][Z]` (UTC) to microseconds since unix epoch
+fn micros(text: &str) -> Result<i64, String> {
+    let invalid = || format!("invalid date or time {text:?}");
+    let trimmed = text.trim();
+    let trimmed = trimmed.strip_suffix('Z').unwrap_or(trimmed);
+    let (date, time) = match trimmed.split_once(['T', ' ']) {
+        Some((date, time)) => (date, Some(time)),
+        None => (trimmed, None),
+    };
+    let [year, month, day] = fields(date, '-')
+        .filter(|[_, month, day]| (1..=12).contains(month) && (1..=31).contains(day))
+        .ok_or_else(invalid)?;
+    let mut micros = days_from_civil(year.into(), month, day) * DAY_MICROS;
+    if let Some(time) = time {
+        let (time, fraction) = time.split_once('.').unwrap_or((time, ""));
+        let [hour, minute, second] = fields(time, ':')
+            .filter(|[hour, minute, second]| *hour < 24 && *minute < 60 && *second < 60)
+            .ok_or_else(invalid)?;
+        if fraction.len() > 6 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let fraction: i64 = format!("{fraction:0<6}").parse().map_err(|_| invalid())?;
+        micros += i64::from(hour * 3600 + minute * 60 + second) * 1_000_000 + fraction;
+    }
+    Ok(micros)
+}
+
+#[test]
+fn parse_values() {
+    use crate::generated::ydb::{DecimalType, OptionalType};
+    let primitive = |id: P| Type { r#type: Some(T::TypeId(id.into())) };
+    let optional = |ty: Type| Type { r#type: Some(T::OptionalType(Box::new(OptionalType { item: Some(Box::new(ty)) }))) };
+    let parse = |text: &str, ty: &Type| parse_value(Some(text), ty).unwrap().value.unwrap();
+
+    assert_eq!(parse("true", &primitive(P::Bool)), V::BoolValue(true));
+    assert_eq!(parse("-5", &primitive(P::Int8)), V::Int32Value(-5));
+    assert!(parse_value(Some("300"), &primitive(P::Uint8)).is_err());
+    assert_eq!(parse("42", &optional(primitive(P::Uint64))), V::Uint64Value(42));
+    assert_eq!(parse("abc", &primitive(P::String)), V::BytesValue(b"abc".to_vec()));
+    assert_eq!(parse_value(None, &optional(primitive(P::Utf8))).unwrap().value, Some(V::NullFlagValue(0)));
+    assert!(parse_value(None, &primitive(P::Utf8)).is_err());
+
+    assert_eq!(parse("1970-01-02", &primitive(P::Date)), V::Uint32Value(1));
+    assert_eq!(parse("2000-03-01", &primitive(P::Date)), V::Uint32Value(11017));
+    assert_eq!(parse("2023-07-01T10:00:00Z", &primitive(P::Datetime)), V::Uint32Value(1688205600));
+    assert_eq!(parse("2023-07-01 10:00:00.25", &primitive(P::Timestamp)), V::Uint64Value(1688205600250000));
+    assert_eq!(parse("1688205600", &primitive(P::Datetime)), V::Uint32Value(1688205600));
+    assert!(parse_value(Some("2023-13-01"), &primitive(P::Date)).is_err());
+    assert!(parse_value(Some("1969-12-31"), &primitive(P::Date)).is_err());
+
+    let decimal_type = Type { r#type: Some(T::DecimalType(DecimalType { precision: 22, scale: 9 })) };
+    let value = parse_value(Some("-1.5"), &decimal_type).unwrap();
+    assert_eq!((value.value, value.high_128), (Some(V::Low128(-1_500_000_000i64 as u64)), u64::MAX));
+    assert!(parse_value(Some("1.0000000001"), &decimal_type).is_err());
+}
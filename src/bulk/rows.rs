@@ -0,0 +1,169 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::error::YdbError;
+use crate::generated::ydb::{Type, Value, TypedValue, StructMember, OptionalType, TupleType};
+use crate::generated::ydb::r#type::{Type as T, PrimitiveTypeId as P};
+use crate::generated::ydb::value::Value as V;
+
+/// Rust value, that can be stored in column of table. Type of column is known by type of value
+pub trait ColumnValue {
+    fn column_type() -> Type;
+    fn into_value(self) -> Value;
+}
+
+fn value(value: V) -> Value {
+    Value { value: Some(value), ..Default::default() }
+}
+
+fn primitive(id: P) -> Type {
+    Type { r#type: Some(T::TypeId(id.into())) }
+}
+
+macro_rules! column_value {
+    ($($t:ty = ($id:ident, $val:ident),)+) => {$(
+        impl ColumnValue for $t {
+            fn column_type() -> Type { primitive(P::$id) }
+            fn into_value(self) -> Value { value(V::$val(self.into())) }
+        }
+    )+};
+}
+
+column_value! {
+    bool = (Bool, BoolValue),
+    i8  = (Int8, Int32Value),
+    u8  = (Uint8, Uint32Value),
+    i16 = (Int16, Int32Value),
+    u16 = (Uint16, Uint32Value),
+    i32 = (Int32, Int32Value),
+    u32 = (Uint32, Uint32Value),
+    i64 = (Int64, Int64Value),
+    u64 = (Uint64, Uint64Value),
+    f32 = (Float, FloatValue),
+    f64 = (Double, DoubleValue),
+    Vec<u8> = (String, BytesValue),
+    String = (Utf8, TextValue),
+}
+
+#[cfg(feature = "sqlx")]
+column_value! {
+    crate::sqlx::types::Date = (Date, Uint32Value),
+    crate::sqlx::types::Datetime = (Datetime, Uint32Value),
+    crate::sqlx::types::Timestamp = (Timestamp, Uint64Value),
+    crate::sqlx::types::Interval = (Interval, Int64Value),
+    crate::sqlx::types::Json = (Json, TextValue),
+    crate::sqlx::types::JsonDocument = (JsonDocument, TextValue),
+}
+
+impl ColumnValue for &str {
+    fn column_type() -> Type { primitive(P::Utf8) }
+    fn into_value(self) -> Value { value(V::TextValue(self.to_owned())) }
+}
+
+/// Stored as `Timestamp` (microseconds since unix epoch)
+impl ColumnValue for SystemTime {
+    fn column_type() -> Type { primitive(P::Timestamp) }
+    fn into_value(self) -> Value {
+        let micros = self.duration_since(UNIX_EPOCH).unwrap_or_default().as_micros();
+        value(V::Uint64Value(micros as u64))
+    }
+}
+
+/// Stored as `Interval` (microseconds)
+impl ColumnValue for Duration {
+    fn column_type() -> Type { primitive(P::Interval) }
+    fn into_value(self) -> Value { value(V::Int64Value(self.as_micros() as i64)) }
+}
+
+impl<I: ColumnValue> ColumnValue for Option<I> {
+    fn column_type() -> Type {
+        Type { r#type: Some(T::OptionalType(Box::new(OptionalType { item: Some(Box::new(I::column_type())) }))) }
+    }
+    fn into_value(self) -> Value {
+        match self {
+            Some(v) => v.into_value(),
+            None => value(V::NullFlagValue(0)),
+        }
+    }
+}
+
+/// Row for `BulkUpsert`: values of columns with their types
+///
+/// # Examples
+/// ```rust
+///     use ydb_unofficial::bulk::Row;
+///     let row = Row::new()
+///         .set("id", 1u64)
+///         .set("name", "alice")
+///         .set("email", None::<String>);
+///     assert_eq!(row.len(), 3);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Row {
+    members: Vec<StructMember>,
+    items: Vec<Value>,
+}
+
+impl Row {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Sets value of column. Type of column is inferred from type of value
+    pub fn set(self, name: &str, value: impl ColumnValue) -> Self {
+        fn typed<I: ColumnValue>(value: I) -> TypedValue {
+            TypedValue { r#type: Some(I::column_type()), value: Some(value.into_value()) }
+        }
+        self.set_typed(name, typed(value))
+    }
+    /// Sets value of column with explicit type (e.g. `Decimal` or containers)
+    pub fn set_typed(mut self, name: &str, value: TypedValue) -> Self {
+        self.members.push(StructMember { name: name.to_owned(), r#type: value.r#type });
+        self.items.push(value.value.unwrap_or_default());
+        self
+    }
+    /// Converts values to tuple (names of columns are dropped), e.g. for key of [`crate::read_table::ReadTableOptions`]
+    pub fn into_tuple(self) -> TypedValue {
+        let elements = self.members.into_iter().filter_map(|m| m.r#type).collect();
+        let r#type = Type { r#type: Some(T::TupleType(TupleType { elements })) };
+        TypedValue { r#type: Some(r#type), value: Some(Value { items: self.items, ..Default::default() }) }
+    }
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+/// Converts rows to `List<Struct<...>>` value for `BulkUpsert`. Type of struct is taken from first row,
+/// all rows must have the same columns with the same types (in the same order)
+pub fn rows_list(rows: impl IntoIterator<Item = Row>) -> Result<TypedValue, YdbError> {
+    let mut rows = rows.into_iter();
+    let Some(first) = rows.next() else {
+        return Err(YdbError::InvalidInput("no rows".to_owned()));
+    };
+    let mut items = vec![Value { items: first.items, ..Default::default() }];
+    for (n, row) in rows.enumerate() {
+        if row.members != first.members {
+            return Err(YdbError::InvalidInput(format!("columns of row {} differ from columns of first row", n + 2)));
+        }
+        items.push(Value { items: row.items, ..Default::default() });
+    }
+    Ok(super::struct_list(first.members, items))
+}
+
+#[test]
+fn rows_with_inferred_types() {
+    let row = |id: u64, name: Option<&str>| Row::new().set("id", id).set("name", name);
+    let list = rows_list([row(1, Some("a")), row(2, None)]).unwrap();
+    let Some(T::ListType(list_type)) = list.r#type.and_then(|t| t.r#type) else { panic!("list expected") };
+    let Some(T::StructType(row_type)) = list_type.item.and_then(|t| t.r#type) else { panic!("struct expected") };
+    assert_eq!(row_type.members, vec![
+        StructMember { name: "id".to_owned(), r#type: Some(primitive(P::Uint64)) },
+        StructMember { name: "name".to_owned(), r#type: Some(Option::<&str>::column_type()) },
+    ]);
+    let rows = list.value.unwrap().items;
+    assert_eq!(rows[1].items, vec![value(V::Uint64Value(2)), value(V::NullFlagValue(0))]);
+
+    assert!(rows_list([row(1, None), Row::new().set("id", 2i64).set("name", "b")]).is_err());
+    assert!(rows_list(Vec::new()).is_err());
+}
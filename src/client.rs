@@ -34,7 +34,8 @@
 //! # }
 //! ```
 use super::*;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
 use error::YdbError;
 use auth::Credentials;
 
@@ -45,12 +46,27 @@ use tonic::service::Interceptor;
 use tonic::transport::{Endpoint, Channel, Uri};
 
 use payload::YdbResponseWithResult;
+use generated::ydb::discovery::EndpointInfo;
 use generated::ydb::discovery::v1::discovery_service_client::DiscoveryServiceClient;
 use generated::ydb::table::transaction_control::TxSelector;
 use generated::ydb::table::{TransactionSettings, ExecuteDataQueryRequest, TransactionControl, self, CreateSessionRequest, DeleteSessionRequest};
 use generated::ydb::table::transaction_settings::TxMode;
 use generated::ydb::table::v1::table_service_client::TableServiceClient;
 use tower::Service;
+use crate::scheme::SchemeClient;
+use crate::scripting::ScriptingClient;
+use crate::operation::OperationClient;
+use crate::coordination::CoordinationClient;
+use crate::rate_limiter::RateLimiterClient;
+use crate::monitoring::{MonitoringClient, HealthReport};
+use crate::topic::TopicClient;
+use crate::bulk::{Row, rows_list};
+use crate::read_rows::ReadRowsClient;
+use crate::session_pool::{SessionPool, SessionPoolOptions, PooledSession};
+use crate::keep_alive::{self, KeepAliveOptions};
+use crate::backup::{ExportClient, ImportClient, S3ExportSettings, S3ImportSettings};
+use crate::generated::ydb::operations::Operation;
+use crate::generated::ydb::ResultSet;
 
 #[derive(Debug, Clone)]
 pub struct YdbEndpoint {
@@ -72,6 +88,21 @@ impl YdbEndpoint {
         }
         e
     }
+    /// Endpoints with the same address share channels, load factor is ignored
+    pub(crate) fn same_address(&self, other: &YdbEndpoint) -> bool {
+        self.ssl == other.ssl && self.host == other.host && self.port == other.port
+    }
+}
+
+impl From<EndpointInfo> for YdbEndpoint {
+    fn from(value: EndpointInfo) -> Self {
+        Self {
+            ssl: value.ssl,
+            host: value.address,
+            port: value.port as u16,
+            load_factor: value.load_factor,
+        }
+    }
 }
 
 impl TryFrom<Uri> for YdbEndpoint {
@@ -120,7 +151,7 @@ pub fn create_endpoint(uri: Uri) -> Endpoint {
 static BUILD_INFO: AsciiValue = concat!("ydb-unofficial/", env!("CARGO_PKG_VERSION")).try_into().unwrap();
 
 #[derive(Clone, Debug)]
-struct DBInterceptor<C: Clone> {
+pub(crate) struct DBInterceptor<C: Clone> {
     db_name: AsciiValue,
     creds: C
 }
@@ -131,15 +162,26 @@ impl<C: Credentials> Interceptor for DBInterceptor<C> {
         headers.insert("x-ydb-database", self.db_name.clone());
         headers.insert("x-ydb-sdk-build-info", BUILD_INFO.clone());
         headers.insert("x-ydb-auth-ticket", self.creds.token());
+        //server sends `session-close` hint before shutdown of node, see process_server_hints
+        headers.insert("x-ydb-client-capabilities", AsciiValue::from_static("session-balancer"));
         Ok(request)    
     }
 }
 
+/// Transport of [`YdbConnection`] without session. Can be cloned to use in background tasks
+pub(crate) type YdbService<C> = InterceptedService<Channel, DBInterceptor<C>>;
+
 /// Ydb connection implementation, that pass database name and auth data to grpc channel
 #[derive(Debug)]
 pub struct YdbConnection<C: Credentials> {
-    inner: InterceptedService<Channel, DBInterceptor<C>>,
+    inner: YdbService<C>,
     session_id: Arc<RwLock<Option<String>>>,
+    /// Time of last use of session, keep-alive skips recently used session
+    last_used: Arc<Mutex<Instant>>,
+    sessions: Option<SessionPool<C>>,
+    keep_alive: Option<KeepAliveOptions>,
+    /// Endpoint of channel, if it is known (e.g. for connections of driver)
+    endpoint: Option<YdbEndpoint>,
 }
 
 
@@ -192,7 +234,7 @@ impl<C: Credentials> YdbConnection<C> {
         let inner = tower::ServiceBuilder::new()
             .layer(tonic::service::interceptor(interceptor))
             .service(channel);
-        YdbConnection{inner, session_id: Arc::new(RwLock::new(None))}
+        YdbConnection{inner, session_id: Arc::new(RwLock::new(None)), last_used: Arc::new(Mutex::new(Instant::now())), sessions: None, keep_alive: None, endpoint: None}
     }
     /// Creates discovery service client
     /// 
@@ -219,6 +261,79 @@ impl<C: Credentials> YdbConnection<C> {
         DiscoveryServiceClient::new(self)
     }
 
+    /// Creates scheme service client to work with directories and paths
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let mut conn = ydb_unofficial::YdbConnection::from_env();
+    ///     let db_name = std::env::var("DB_NAME").unwrap();
+    ///     let entries = conn.scheme().list_directory_recursive(&db_name).await.unwrap();
+    ///     for (path, entry) in entries {
+    ///         println!("{path}: {:?}", entry.r#type());
+    ///     }
+    /// # }
+    /// ```
+    pub fn scheme(&mut self) -> SchemeClient<'_, C> {
+        SchemeClient::new(self)
+    }
+    /// Creates scripting service client to execute YQL scripts, that mix DDL and DML queries.
+    /// See examples in [`crate::scripting`]
+    pub fn scripting(&mut self) -> ScriptingClient<'_, C> {
+        ScriptingClient::new(self)
+    }
+    /// Creates operation service client to poll and manage long-running operations.
+    /// See examples in [`crate::operation`]
+    pub fn operation(&mut self) -> OperationClient<'_, C> {
+        OperationClient::new(self)
+    }
+    /// Creates coordination service client to manage coordination nodes and start sessions with semaphores.
+    /// See examples in [`crate::coordination`]
+    pub fn coordination(&mut self) -> CoordinationClient<'_, C> {
+        CoordinationClient::new(self)
+    }
+    /// Creates rate limiter service client. See examples in [`crate::rate_limiter`]
+    pub fn rate_limiter(&mut self) -> RateLimiterClient<'_, C> {
+        RateLimiterClient::new(self)
+    }
+    /// Creates monitoring service client
+    pub fn monitoring(&mut self) -> MonitoringClient<'_, C> {
+        MonitoringClient::new(self)
+    }
+    /// Creates topic service client to manage topics, write and read messages. See [`crate::topic`]
+    pub fn topic(&mut self) -> TopicClient<'_, C> {
+        TopicClient::new(self)
+    }
+    /// Checks health of database with `SelfCheck` request. See examples in [`crate::monitoring`]
+    pub async fn health(&mut self) -> Result<HealthReport, YdbError> {
+        self.monitoring().health().await
+    }
+    /// Creates export service client
+    pub fn export(&mut self) -> ExportClient<'_, C> {
+        ExportClient::new(self)
+    }
+    /// Creates import service client
+    pub fn import(&mut self) -> ImportClient<'_, C> {
+        ImportClient::new(self)
+    }
+    /// Starts export of tables to S3. Returns long-running operation. See examples in [`crate::backup`]
+    pub async fn export_to_s3(&mut self, settings: S3ExportSettings) -> Result<Operation, YdbError> {
+        self.export().to_s3(settings).await
+    }
+    /// Starts import of tables from S3. Returns long-running operation. See [`crate::backup`]
+    pub async fn import_from_s3(&mut self, settings: S3ImportSettings) -> Result<Operation, YdbError> {
+        self.import().from_s3(settings).await
+    }
+    /// Creates client of `ReadRows` method
+    pub fn read_rows_client(&mut self) -> ReadRowsClient<'_, C> {
+        ReadRowsClient::new(self)
+    }
+    /// Reads rows of table by primary keys without YQL query. See examples in [`crate::read_rows`]
+    pub async fn read_rows(&mut self, table: &str, keys: impl IntoIterator<Item = Row>, columns: &[&str]) -> Result<ResultSet, YdbError> {
+        self.read_rows_client().read(table, keys, columns).await
+    }
+
     /// Creates session and returns [`TableClientWithSession`]
     /// # Examples
     /// ```rust
@@ -231,7 +346,7 @@ impl<C: Credentials> YdbConnection<C> {
     ///     //..some another code
     /// # }
     /// ```
-    pub async fn table(&mut self) -> Result<TableClientWithSession<C>, YdbError> {
+    pub async fn table(&mut self) -> Result<TableClientWithSession<'_, C>, YdbError> {
         let session_id = if let Some(session_id) = self.session_id() {
             session_id
         } else {
@@ -242,18 +357,40 @@ impl<C: Credentials> YdbConnection<C> {
             *self.session_id.write().unwrap() = Some(session_id.clone());
             session_id
         };
+        *self.last_used.lock().unwrap() = Instant::now();
         let session_ref = self.session_id.clone();
         let client = TableServiceClient::new(self);
         Ok(TableClientWithSession {session_ref, session_id, client })
     }
-    pub fn table_if_ready(&mut self) -> Option<TableClientWithSession<C>> {
+    pub fn table_if_ready(&mut self) -> Option<TableClientWithSession<'_, C>> {
         let session_id = self.session_id()?;
+        *self.last_used.lock().unwrap() = Instant::now();
         let session_ref = self.session_id.clone();
         Some(TableClientWithSession {session_ref, session_id, client: TableServiceClient::new(self) })
     }
+    /// Like [`YdbConnection::table_if_ready`], but without session returns client with empty session id.
+    /// Such client creates session on [`TableClientWithSession::ensure_session`]
+    #[cfg(feature = "sqlx")]
+    pub(crate) fn table_lazy(&mut self) -> TableClientWithSession<'_, C> {
+        let session_id = self.session_id().unwrap_or_default();
+        *self.last_used.lock().unwrap() = Instant::now();
+        let session_ref = self.session_id.clone();
+        TableClientWithSession {session_ref, session_id, client: TableServiceClient::new(self) }
+    }
     fn session_id(&self) -> Option<String> {
         self.session_id.read().unwrap().clone()
     }
+    /// Endpoint, that connection is bound to. It is known for connections, created by [`crate::driver::Driver`]
+    pub fn endpoint(&self) -> Option<&YdbEndpoint> {
+        self.endpoint.as_ref()
+    }
+    pub(crate) fn with_endpoint(mut self, endpoint: YdbEndpoint) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+    pub(crate) fn service(&self) -> YdbService<C> {
+        self.inner.clone()
+    }
     pub async fn close_session(&mut self) -> Result<(), YdbError> {
         delete_session(&self.session_id, self.inner.clone()).await?;
         Ok(())
@@ -265,7 +402,7 @@ impl<C: Credentials> YdbConnection<C> {
 }
 
 
-async fn delete_session<C: Credentials>(session_ref: &Arc<RwLock<Option<String>>>, service: InterceptedService<Channel, DBInterceptor<C>>)  -> Result<(), YdbError> {
+async fn delete_session<C: Credentials>(session_ref: &Arc<RwLock<Option<String>>>, service: YdbService<C>)  -> Result<(), YdbError> {
     let session_id = session_ref.read().unwrap().clone();
     if let Some(session_id) = session_id {
         let mut client = TableServiceClient::new(service);
@@ -277,6 +414,44 @@ async fn delete_session<C: Credentials>(session_ref: &Arc<RwLock<Option<String>>
     Ok(())
 }
 
+/// Pool of sessions and keep-alive run in background tasks, so they require `Sync` credentials
+impl<C: Credentials + Sync> YdbConnection<C> {
+    /// Sets settings of pool of sessions (see [`YdbConnection::sessions`])
+    pub fn with_session_pool(mut self, options: SessionPoolOptions) -> Self {
+        self.sessions = Some(self.create_session_pool(options));
+        self
+    }
+    fn create_session_pool(&self, options: SessionPoolOptions) -> SessionPool<C> {
+        let pool = SessionPool::new(self.inner.clone(), options);
+        if let Some(keep_alive) = &self.keep_alive {
+            pool.spawn_keep_alive(keep_alive.clone());
+        }
+        pool
+    }
+    /// Returns pool of sessions of this connection (it is created with default settings on first call).
+    /// Pool is independent from session of [`YdbConnection::table`]. See [`crate::session_pool`]
+    pub fn sessions(&mut self) -> SessionPool<C> {
+        if self.sessions.is_none() {
+            self.sessions = Some(self.create_session_pool(Default::default()));
+        }
+        self.sessions.clone().unwrap()
+    }
+    /// Starts background keep-alive of session of this connection (and of its pool of sessions).
+    /// Must be called inside tokio runtime. See [`crate::keep_alive`]
+    pub fn with_keep_alive(mut self, options: KeepAliveOptions) -> Self {
+        keep_alive::spawn_for_connection(self.inner.clone(), Arc::downgrade(&self.session_id), self.last_used.clone(), options.clone());
+        if let Some(sessions) = &self.sessions {
+            sessions.spawn_keep_alive(options.clone());
+        }
+        self.keep_alive = Some(options);
+        self
+    }
+    /// Takes session from pool, see [`YdbConnection::sessions`]
+    pub async fn session(&mut self) -> Result<PooledSession<C>, YdbError> {
+        self.sessions().acquire().await
+    }
+}
+
 impl<C: Credentials> Drop for YdbConnection<C> {
     fn drop(&mut self) {
         if let Some(session_id) = self.session_id() {
@@ -305,7 +480,7 @@ pub struct TableClientWithSession<'a, C: Credentials> {
     client: TableServiceClient<&'a mut YdbConnection<C>>,
 }
 
-fn process_session_fail(
+pub(crate) fn process_session_fail(
     code: crate::generated::ydb::status_ids::StatusCode, 
     session_ref: &Arc<RwLock<Option<String>>>,
 ) {
@@ -318,11 +493,33 @@ fn process_session_fail(
     }
 }
 
+/// Checks `x-ydb-server-hints` of response. When node is going to shut down, it sends `session-close` hint:
+/// session is still valid for current request, but it must not be used anymore.
+/// So session is released, and the next request gets new session (like after `BadSession`)
+pub(crate) fn process_server_hints(
+    metadata: &tonic::metadata::MetadataMap,
+    session_ref: &Arc<RwLock<Option<String>>>,
+) {
+    if has_session_close_hint(metadata) {
+        if let Some(session_id) = session_ref.write().unwrap().take() {
+            log::debug!("Server requested to close session {session_id}");
+        }
+    }
+}
+
+pub(crate) fn has_session_close_hint(metadata: &tonic::metadata::MetadataMap) -> bool {
+    metadata.get_all("x-ydb-server-hints").iter()
+        .filter_map(|v| v.to_str().ok())
+        .any(|v| v.split(',').any(|hint| hint.trim() == "session-close"))
+}
+
 macro_rules! delegate {
     (with $field:ident : $(fn $fun:ident($arg:ty) -> $ret:ty;)+) => { $(
         pub async fn $fun(&mut self, mut req: $arg) -> Result<tonic::Response<$ret>, YdbError> {
             req.$field = self.$field.clone();
-            let result = self.client.$fun(req).await?;
+            let result = self.client.$fun(req).await
+                .map_err(|e| { process_server_hints(e.metadata(), &self.session_ref); e })?;
+            process_server_hints(result.metadata(), &self.session_ref);
             let status = result.get_ref().operation.as_ref().ok_or(YdbError::EmptyResponse)?.status();
             use crate::generated::ydb::status_ids::StatusCode;
             use crate::error::ErrWithOperation;
@@ -334,9 +531,23 @@ macro_rules! delegate {
                 },
             }
         }
-    )+} 
+    )+};
+    ($(fn $fun:ident($arg:ty) -> $ret:ty;)+) => { $(
+        pub async fn $fun(&mut self, req: $arg) -> Result<tonic::Response<$ret>, $crate::error::YdbError> {
+            use $crate::error::{YdbError, ErrWithOperation};
+            use $crate::generated::ydb::status_ids::StatusCode;
+            let result = self.client.$fun(req).await?;
+            let status = result.get_ref().operation.as_ref().ok_or(YdbError::EmptyResponse)?.status();
+            match status {
+                StatusCode::Success => Ok(result),
+                _ => Err(YdbError::Ydb(ErrWithOperation(result.into_inner().operation.unwrap()))),
+            }
+        }
+    )+};
 }
 
+pub(crate) use delegate;
+
 
 impl <'a, C: Credentials + Send> TableClientWithSession<'a, C> {
     delegate!{ with session_id:
@@ -356,9 +567,27 @@ impl <'a, C: Credentials + Send> TableClientWithSession<'a, C> {
         fn rollback_transaction(RollbackTransactionRequest) -> RollbackTransactionResponse;
         fn delete_session(DeleteSessionRequest) -> DeleteSessionResponse;
     }
+    /// Server hints of initial headers are processed here. Hints of trailers (e.g. `session-close`) arrive
+    /// at the end of stream, they are processed by [`Self::read_table`]
     pub async fn stream_read_table(&mut self, mut req: ReadTableRequest) -> Result<tonic::Response<tonic::codec::Streaming<ReadTableResponse>>, tonic::Status> {
         req.session_id = self.session_id.clone();
-        self.client.stream_read_table(req).await
+        let result = self.client.stream_read_table(req).await;
+        match &result {
+            Ok(response) => process_server_hints(response.metadata(), &self.session_ref),
+            Err(e) => process_server_hints(e.metadata(), &self.session_ref),
+        }
+        result
+    }
+    pub(crate) fn session_ref(&self) -> Arc<RwLock<Option<String>>> {
+        self.session_ref.clone()
+    }
+    /// Upserts rows into table without transaction. Type of rows is inferred from their values, see [`Row`].
+    /// Returns [`YdbError::InvalidInput`], if there are no rows, or rows have different columns (see [`crate::bulk::rows_list`])
+    pub async fn bulk_upsert(&mut self, table: &str, rows: impl IntoIterator<Item = Row>) -> Result<(), YdbError> {
+        let req = BulkUpsertRequest { table: table.to_owned(), rows: Some(rows_list(rows)?), ..Default::default() };
+        let response = self.client.bulk_upsert(req).await?;
+        let operation = response.into_inner().operation.ok_or(YdbError::EmptyResponse)?;
+        error::check_status(operation.status, operation.issues)
     }
     pub async fn update_session(&mut self) -> Result<(), YdbError> {
         let response = self.client.create_session(CreateSessionRequest::default()).await?;
@@ -368,6 +597,14 @@ impl <'a, C: Credentials + Send> TableClientWithSession<'a, C> {
         self.session_id = session_id;
         Ok(())
     }
+    /// Creates session, if client has no one (see [`YdbConnection::table_lazy`])
+    #[cfg(feature = "sqlx")]
+    pub(crate) async fn ensure_session(&mut self) -> Result<(), YdbError> {
+        if self.session_id.is_empty() {
+            self.update_session().await?;
+        }
+        Ok(())
+    }
 }
 
 /// [`TableServiceClient`] with active session and transaction
@@ -397,9 +634,11 @@ pub struct YdbTransaction<'a, C: Credentials> {
 /// # }
 /// ```
 impl<'a, C: Credentials> YdbTransaction<'a, C> {
+    #[cfg(feature = "sqlx")]
     pub(crate) fn new(client: TableClientWithSession<'a, C>, tx_control: TransactionControl) -> Self {
         Self {client, tx_control}
     }
+    #[cfg(feature = "sqlx")]
     pub(crate) fn table_client(&mut self) -> &mut TableClientWithSession<'a, C> {
         &mut self.client
     }
@@ -446,3 +685,14 @@ impl<'a, C: Credentials> YdbTransaction<'a, C> {
         self.client.execute_data_query(req).await
     }
 }
+
+#[test]
+fn session_close_hint() {
+    let session_ref = Arc::new(RwLock::new(Some("s1".to_owned())));
+    let mut metadata = tonic::metadata::MetadataMap::new();
+    process_server_hints(&metadata, &session_ref);
+    assert!(session_ref.read().unwrap().is_some());
+    metadata.insert("x-ydb-server-hints", AsciiValue::from_static("session-close"));
+    process_server_hints(&metadata, &session_ref);
+    assert!(session_ref.read().unwrap().is_none());
+}
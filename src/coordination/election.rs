@@ -0,0 +1,187 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::{oneshot, watch};
+
+use crate::error::{YdbError, ErrWithOperation};
+use crate::generated::ydb::status_ids::StatusCode;
+use crate::generated::ydb::coordination::{SemaphoreDescription, SemaphoreSession};
+use super::session::*;
+
+/// Current leader of election
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leader {
+    /// Id of coordination session of leader
+    pub session_id: u64,
+    /// Data, that leader passed to [`LeaderElection::campaign`] (e.g. its address)
+    pub data: Vec<u8>,
+}
+
+impl From<SemaphoreSession> for Leader {
+    fn from(value: SemaphoreSession) -> Self {
+        Self { session_id: value.session_id, data: value.data }
+    }
+}
+
+fn leader_of(description: SemaphoreDescription) -> Option<Leader> {
+    description.owners.into_iter().next().map(Leader::from)
+}
+
+/// State of [`Leadership`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeadershipState {
+    /// Session is connected and holds leadership
+    Leader,
+    /// Session is restoring after connection lost. Leadership is kept until session timeout, but it is not guaranteed
+    Reconnecting,
+    /// Session is lost or leadership is resigned
+    Lost,
+}
+
+impl From<SessionState> for LeadershipState {
+    fn from(value: SessionState) -> Self {
+        match value {
+            SessionState::Connected => Self::Leader,
+            SessionState::Reconnecting => Self::Reconnecting,
+            SessionState::Lost => Self::Lost,
+        }
+    }
+}
+
+/// Leader election on semaphore of coordination node. Semaphore has limit 1, so only one session can own it.
+///
+/// # Examples
+/// ```rust,no_run
+/// # #[tokio::main]
+/// # async fn main() {
+///     use ydb_unofficial::coordination::{SessionOptions, LeadershipState};
+///     let mut conn = ydb_unofficial::YdbConnection::from_env();
+///     let election = conn.coordination().leader_election("scheduler", "leader", SessionOptions::default()).await.unwrap();
+///     let leadership = election.campaign(b"host-1:8080".to_vec()).await.unwrap();
+///     let mut state = leadership.state();
+///     while *state.borrow_and_update() == LeadershipState::Leader {
+///         //do leader work...
+///         state.changed().await.unwrap();
+///     }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct LeaderElection {
+    session: CoordinationSession,
+    name: String,
+}
+
+impl LeaderElection {
+    /// Creates election on semaphore `name` (semaphore will be created if not exists)
+    pub async fn new(session: CoordinationSession, name: &str) -> Result<Self, YdbError> {
+        match session.create_semaphore(name, 1, vec![]).await {
+            Err(YdbError::Ydb(ErrWithOperation(op))) if op.status() == StatusCode::AlreadyExists => {}
+            result => result?,
+        }
+        Ok(Self { session, name: name.to_owned() })
+    }
+    pub fn session(&self) -> &CoordinationSession {
+        &self.session
+    }
+    /// Waits until this session becomes leader. `data` is visible to other participants as [`Leader::data`]
+    pub async fn campaign(&self, data: Vec<u8>) -> Result<Leadership, YdbError> {
+        loop {
+            if let Some(leadership) = self.acquire(data.clone(), None).await? {
+                return Ok(leadership);
+            }
+        }
+    }
+    /// Tries to become leader without waiting. Returns `None` if there is another leader
+    pub async fn try_campaign(&self, data: Vec<u8>) -> Result<Option<Leadership>, YdbError> {
+        self.acquire(data, Some(Duration::ZERO)).await
+    }
+    async fn acquire(&self, data: Vec<u8>, timeout: Option<Duration>) -> Result<Option<Leadership>, YdbError> {
+        let options = AcquireOptions { timeout, data, ephemeral: false };
+        let guard = self.session.acquire(&self.name, 1, options).await?;
+        Ok(guard.map(Leadership::new))
+    }
+    /// Returns current leader (if any)
+    pub async fn leader(&self) -> Result<Option<Leader>, YdbError> {
+        let options = DescribeOptions { include_owners: true, ..Default::default() };
+        let description = self.session.describe_semaphore(&self.name, options).await?;
+        Ok(leader_of(description))
+    }
+    /// Watches current leader in background task. Task stops when all receivers dropped or session lost
+    pub fn observe(&self) -> watch::Receiver<Option<Leader>> {
+        let (sender, receiver) = watch::channel(None);
+        let session = self.session.clone();
+        let name = self.name.clone();
+        tokio::spawn(async move {
+            let options = DescribeOptions { include_owners: true, watch_owners: true, watch_data: true, ..Default::default() };
+            loop {
+                let changed = match session.watch_semaphore(&name, options).await {
+                    Ok((description, changed)) => {
+                        sender.send_replace(leader_of(description));
+                        changed
+                    }
+                    Err(YdbError::SessionClosed) => break,
+                    Err(e) => {
+                        log::warn!("Cannot describe leader of election {name}: {e}");
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                        continue;
+                    }
+                };
+                tokio::select! {
+                    _ = changed => {}
+                    _ = sender.closed() => break,
+                }
+            }
+            sender.send_replace(None);
+        });
+        receiver
+    }
+}
+
+/// Leadership of session. Leadership is resigned on drop (semaphore is released in background)
+#[derive(Debug)]
+pub struct Leadership {
+    guard: SemaphoreGuard,
+    state: Arc<watch::Sender<LeadershipState>>,
+    _stop_watch: oneshot::Sender<()>,
+}
+
+impl Leadership {
+    fn new(guard: SemaphoreGuard) -> Self {
+        let mut session_state = guard.session().state_watch();
+        let state = Arc::new(watch::channel(LeadershipState::from(*session_state.borrow_and_update())).0);
+        let (stop_watch, mut stopped) = oneshot::channel();
+        let sender = state.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    changed = session_state.changed() => {
+                        let current = if changed.is_ok() { LeadershipState::from(*session_state.borrow_and_update()) } else { LeadershipState::Lost };
+                        sender.send_replace(current);
+                        if current == LeadershipState::Lost {
+                            break;
+                        }
+                    }
+                    _ = &mut stopped => {
+                        sender.send_replace(LeadershipState::Lost);
+                        break;
+                    }
+                }
+            }
+        });
+        Self { guard, state, _stop_watch: stop_watch }
+    }
+    /// Returns receiver to watch state of leadership. It flips to [`LeadershipState::Lost`] when session dies or leadership resigned
+    pub fn state(&self) -> watch::Receiver<LeadershipState> {
+        self.state.subscribe()
+    }
+    pub fn is_leader(&self) -> bool {
+        *self.state.borrow() == LeadershipState::Leader
+    }
+    /// Releases leadership, so another participant can become leader
+    pub async fn resign(self) -> Result<(), YdbError> {
+        let Self { guard, state, .. } = self;
+        state.send_replace(LeadershipState::Lost);
+        guard.release().await?;
+        Ok(())
+    }
+}
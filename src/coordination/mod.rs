@@ -0,0 +1,77 @@
+//! Coordination service client: coordination nodes, sessions and semaphores.
+//! Semaphores can be used for distributed locks (see [`CoordinationSession::lock`]) and leader election (see [`LeaderElection`])
+//!
+//! # Examples
+//! ```rust,no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//!     use ydb_unofficial::coordination::SessionOptions;
+//!     let mut conn = ydb_unofficial::YdbConnection::from_env();
+//!     let mut coordination = conn.coordination();
+//!     coordination.create("locks", Default::default()).await.unwrap();
+//!     let session = coordination.session("locks", SessionOptions::default()).await.unwrap();
+//!     if let Some(guard) = session.lock("my-job", None).await.unwrap() {
+//!         //do something exclusively...
+//!         guard.release().await.unwrap();
+//!     }
+//!     session.stop().await;
+//! # }
+//! ```
+mod session;
+mod election;
+
+pub use session::*;
+pub use election::*;
+
+use super::*;
+use auth::Credentials;
+use client::{delegate, YdbConnection, YdbService};
+use error::YdbError;
+use payload::YdbResponseWithResult;
+
+use generated::ydb::coordination::v1::coordination_service_client::CoordinationServiceClient;
+use generated::ydb::coordination::*;
+
+/// [`CoordinationServiceClient`] wrapper, that checks status of each response.
+/// Use [`YdbConnection::coordination`] to create it
+#[derive(Debug)]
+pub struct CoordinationClient<'a, C: Credentials> {
+    service: YdbService<C>,
+    client: CoordinationServiceClient<&'a mut YdbConnection<C>>,
+}
+
+impl<'a, C: Credentials> CoordinationClient<'a, C> {
+    pub(crate) fn new(conn: &'a mut YdbConnection<C>) -> Self {
+        let service = conn.service();
+        Self { service, client: CoordinationServiceClient::new(conn) }
+    }
+    delegate!{
+        fn create_node(CreateNodeRequest) -> CreateNodeResponse;
+        fn alter_node(AlterNodeRequest) -> AlterNodeResponse;
+        fn drop_node(DropNodeRequest) -> DropNodeResponse;
+        fn describe_node(DescribeNodeRequest) -> DescribeNodeResponse;
+    }
+    /// Creates coordination node by path
+    pub async fn create(&mut self, path: &str, config: Config) -> Result<(), YdbError> {
+        self.create_node(CreateNodeRequest { path: path.to_owned(), config: Some(config), ..Default::default() }).await?;
+        Ok(())
+    }
+    /// Drops coordination node by path
+    pub async fn drop(&mut self, path: &str) -> Result<(), YdbError> {
+        self.drop_node(DropNodeRequest { path: path.to_owned(), ..Default::default() }).await?;
+        Ok(())
+    }
+    pub async fn describe(&mut self, path: &str) -> Result<DescribeNodeResult, YdbError> {
+        let response = self.describe_node(DescribeNodeRequest { path: path.to_owned(), ..Default::default() }).await?;
+        Ok(response.into_inner().result()?)
+    }
+    /// Starts session on coordination node. Session works in background task and doesn't borrow connection
+    pub async fn session(&self, path: &str, options: SessionOptions) -> Result<CoordinationSession, YdbError> {
+        CoordinationSession::start(self.service.clone(), path, options).await
+    }
+    /// Starts session on coordination node and creates [`LeaderElection`] on semaphore `name`
+    pub async fn leader_election(&self, path: &str, name: &str, options: SessionOptions) -> Result<LeaderElection, YdbError> {
+        let session = self.session(path, options).await?;
+        LeaderElection::new(session, name).await
+    }
+}
@@ -0,0 +1,469 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+
+use futures::channel::mpsc::{unbounded, UnboundedSender};
+use tokio::sync::{mpsc, oneshot, watch};
+use tonic::codec::Streaming;
+
+use crate::auth::Credentials;
+use crate::client::YdbService;
+use crate::error::{YdbError, ErrWithOperation, check_status};
+use crate::generated::ydb::status_ids::StatusCode;
+use crate::generated::ydb::coordination::v1::coordination_service_client::CoordinationServiceClient;
+use crate::generated::ydb::coordination::{SessionRequest, SessionResponse, SemaphoreDescription};
+use crate::generated::ydb::coordination::session_request::{self as req, Request};
+use crate::generated::ydb::coordination::session_response::{self as resp, Response};
+
+/// Count to acquire semaphore exclusively. Ephemeral semaphores have limit equal to that value
+pub const EXCLUSIVE: u64 = u64::MAX;
+
+/// Settings of [`CoordinationSession`]
+#[derive(Debug, Clone)]
+pub struct SessionOptions {
+    /// Server keeps session (and its semaphores) during this time after connection lost
+    pub timeout: Duration,
+    /// Description of session, visible in semaphore owners and waiters
+    pub description: String,
+    /// Delay between attempts to restore session after transport error
+    pub reconnect_interval: Duration,
+}
+
+impl Default for SessionOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(5),
+            description: String::new(),
+            reconnect_interval: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Settings to acquire semaphore
+#[derive(Debug, Clone, Default)]
+pub struct AcquireOptions {
+    /// Max time to wait in queue of semaphore. `None` means wait forever, zero means try to acquire without waiting
+    pub timeout: Option<Duration>,
+    /// Data of owner, visible to other sessions in semaphore description
+    pub data: Vec<u8>,
+    /// Ephemeral semaphore is created on acquire and deleted after last release
+    pub ephemeral: bool,
+}
+
+/// Settings to describe semaphore
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DescribeOptions {
+    pub include_owners: bool,
+    pub include_waiters: bool,
+    /// Notify when data of semaphore changed (only for [`CoordinationSession::watch_semaphore`])
+    pub watch_data: bool,
+    /// Notify when owners of semaphore changed (only for [`CoordinationSession::watch_semaphore`])
+    pub watch_owners: bool,
+}
+
+/// State of coordination session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionState {
+    /// Session is connected to server
+    Connected,
+    /// Connection lost, session is restoring. Server keeps acquired semaphores until session timeout
+    Reconnecting,
+    /// Session is expired or stopped, all its semaphores are released
+    Lost,
+}
+
+type Reply = oneshot::Sender<Result<Response, YdbError>>;
+
+enum Command {
+    Request {
+        request: Request,
+        reply: Option<Reply>,
+        changed: Option<oneshot::Sender<resp::DescribeSemaphoreChanged>>,
+    },
+    Stop(oneshot::Sender<()>),
+}
+
+/// Handle of coordination session, that works in background task.
+/// Session restores itself after transport errors. It stops, when all handles (and guards) are dropped
+#[derive(Debug, Clone)]
+pub struct CoordinationSession {
+    commands: mpsc::UnboundedSender<Command>,
+    state: watch::Receiver<SessionState>,
+    session_id: u64,
+}
+
+macro_rules! expect {
+    ($response:expr, $variant:ident) => {
+        match $response {
+            Response::$variant(r) => r,
+            _ => return Err(YdbError::EmptyResponse),
+        }
+    };
+}
+
+impl CoordinationSession {
+    pub(crate) async fn start<C: Credentials>(service: YdbService<C>, path: &str, options: SessionOptions) -> Result<Self, YdbError> {
+        let (state_sender, state) = watch::channel(SessionState::Connected);
+        let mut actor = Actor {
+            client: CoordinationServiceClient::new(service),
+            path: path.to_owned(),
+            protection_key: rand::random::<[u8; 16]>().to_vec(),
+            options,
+            session_id: 0,
+            seq_no: 0,
+            next_req_id: 1,
+            pending: HashMap::new(),
+            watchers: HashMap::new(),
+            state: state_sender,
+        };
+        let connection = actor.connect().await?;
+        let session_id = actor.session_id;
+        log::debug!("Coordination session started: {session_id}");
+        let (commands, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(actor.run(connection, receiver));
+        Ok(Self { commands, state, session_id })
+    }
+    pub fn session_id(&self) -> u64 {
+        self.session_id
+    }
+    pub fn state(&self) -> SessionState {
+        *self.state.borrow()
+    }
+    /// Returns receiver to watch state changes of session
+    pub fn state_watch(&self) -> watch::Receiver<SessionState> {
+        self.state.clone()
+    }
+    fn send(&self, request: Request, reply: Option<Reply>, changed: Option<oneshot::Sender<resp::DescribeSemaphoreChanged>>) -> Result<(), YdbError> {
+        self.commands.send(Command::Request { request, reply, changed }).map_err(|_| YdbError::SessionClosed)
+    }
+    async fn request(&self, request: Request) -> Result<Response, YdbError> {
+        let (reply, response) = oneshot::channel();
+        self.send(request, Some(reply), None)?;
+        response.await.map_err(|_| YdbError::SessionClosed)?
+    }
+    pub async fn create_semaphore(&self, name: &str, limit: u64, data: Vec<u8>) -> Result<(), YdbError> {
+        let request = Request::CreateSemaphore(req::CreateSemaphore { req_id: 0, name: name.to_owned(), limit, data });
+        let result = expect!(self.request(request).await?, CreateSemaphoreResult);
+        check_status(result.status, result.issues)
+    }
+    pub async fn update_semaphore(&self, name: &str, data: Vec<u8>) -> Result<(), YdbError> {
+        let request = Request::UpdateSemaphore(req::UpdateSemaphore { req_id: 0, name: name.to_owned(), data });
+        let result = expect!(self.request(request).await?, UpdateSemaphoreResult);
+        check_status(result.status, result.issues)
+    }
+    /// Deletes semaphore. If `force` is set, semaphore will be deleted even if it has owners or waiters
+    pub async fn delete_semaphore(&self, name: &str, force: bool) -> Result<(), YdbError> {
+        let request = Request::DeleteSemaphore(req::DeleteSemaphore { req_id: 0, name: name.to_owned(), force });
+        let result = expect!(self.request(request).await?, DeleteSemaphoreResult);
+        check_status(result.status, result.issues)
+    }
+    /// Acquires `count` units of semaphore. Returns `false` if timeout of waiting exceeded
+    pub async fn acquire_semaphore(&self, name: &str, count: u64, options: AcquireOptions) -> Result<bool, YdbError> {
+        let AcquireOptions { timeout, data, ephemeral } = options;
+        let timeout_millis = timeout.map(|t| t.as_millis() as u64).unwrap_or(u64::MAX);
+        let request = Request::AcquireSemaphore(req::AcquireSemaphore { req_id: 0, name: name.to_owned(), timeout_millis, count, data, ephemeral });
+        let result = expect!(self.request(request).await?, AcquireSemaphoreResult);
+        check_status(result.status, result.issues)?;
+        Ok(result.acquired)
+    }
+    /// Releases semaphore (or removes session from queue of waiters). Returns `false` if semaphore was not acquired
+    pub async fn release_semaphore(&self, name: &str) -> Result<bool, YdbError> {
+        let request = Request::ReleaseSemaphore(req::ReleaseSemaphore { req_id: 0, name: name.to_owned() });
+        let result = expect!(self.request(request).await?, ReleaseSemaphoreResult);
+        check_status(result.status, result.issues)?;
+        Ok(result.released)
+    }
+    pub async fn describe_semaphore(&self, name: &str, options: DescribeOptions) -> Result<SemaphoreDescription, YdbError> {
+        let request = describe_request(name, DescribeOptions { watch_data: false, watch_owners: false, ..options });
+        let result = expect!(self.request(request).await?, DescribeSemaphoreResult);
+        check_status(result.status, result.issues)?;
+        result.semaphore_description.ok_or(YdbError::EmptyResponse)
+    }
+    /// Describes semaphore and subscribes to its changes (see `watch_data` and `watch_owners` of [`DescribeOptions`]).
+    /// Returned future resolves on first change. It also resolves with error if watch was lost (e.g. on reconnect),
+    /// in that case semaphore needs to be described again
+    pub async fn watch_semaphore(&self, name: &str, options: DescribeOptions) -> Result<(SemaphoreDescription, impl Future<Output = Result<resp::DescribeSemaphoreChanged, YdbError>>), YdbError> {
+        let (reply, response) = oneshot::channel();
+        let (changed_sender, changed) = oneshot::channel();
+        self.send(describe_request(name, options), Some(reply), Some(changed_sender))?;
+        let result = expect!(response.await.map_err(|_| YdbError::SessionClosed)??, DescribeSemaphoreResult);
+        check_status(result.status, result.issues)?;
+        let description = result.semaphore_description.ok_or(YdbError::EmptyResponse)?;
+        Ok((description, async move { changed.await.map_err(|_| YdbError::SessionClosed) }))
+    }
+    /// Acquires semaphore and returns guard, that releases semaphore on drop. Returns `None` if timeout of waiting exceeded
+    pub async fn acquire(&self, name: &str, count: u64, options: AcquireOptions) -> Result<Option<SemaphoreGuard>, YdbError> {
+        if self.acquire_semaphore(name, count, options).await? {
+            Ok(Some(SemaphoreGuard { session: self.clone(), name: name.to_owned(), released: false }))
+        } else {
+            Ok(None)
+        }
+    }
+    /// Takes exclusive lock on ephemeral semaphore. Returns `None` if timeout of waiting exceeded
+    pub async fn lock(&self, name: &str, timeout: Option<Duration>) -> Result<Option<SemaphoreGuard>, YdbError> {
+        self.acquire(name, EXCLUSIVE, AcquireOptions { timeout, ephemeral: true, ..Default::default() }).await
+    }
+    /// Stops session on server, all semaphores of session will be released.
+    /// Session also stops, when all handles are dropped
+    pub async fn stop(self) {
+        let (sender, stopped) = oneshot::channel();
+        if self.commands.send(Command::Stop(sender)).is_ok() {
+            let _ = stopped.await;
+        }
+    }
+}
+
+fn describe_request(name: &str, options: DescribeOptions) -> Request {
+    let DescribeOptions { include_owners, include_waiters, watch_data, watch_owners } = options;
+    Request::DescribeSemaphore(req::DescribeSemaphore {
+        req_id: 0,
+        name: name.to_owned(),
+        include_owners,
+        include_waiters,
+        watch_data,
+        watch_owners,
+    })
+}
+
+/// Acquired semaphore. Releases semaphore on drop (in background)
+#[derive(Debug)]
+pub struct SemaphoreGuard {
+    session: CoordinationSession,
+    name: String,
+    released: bool,
+}
+
+impl SemaphoreGuard {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn session(&self) -> &CoordinationSession {
+        &self.session
+    }
+    /// Releases semaphore and waits for result
+    pub async fn release(mut self) -> Result<bool, YdbError> {
+        self.released = true;
+        self.session.release_semaphore(&self.name).await
+    }
+}
+
+impl Drop for SemaphoreGuard {
+    fn drop(&mut self) {
+        if !self.released {
+            let request = Request::ReleaseSemaphore(req::ReleaseSemaphore { req_id: 0, name: self.name.clone() });
+            let _ = self.session.send(request, None, None);
+        }
+    }
+}
+
+fn set_req_id(request: &mut Request, id: u64) {
+    match request {
+        Request::AcquireSemaphore(r) => r.req_id = id,
+        Request::ReleaseSemaphore(r) => r.req_id = id,
+        Request::DescribeSemaphore(r) => r.req_id = id,
+        Request::CreateSemaphore(r) => r.req_id = id,
+        Request::UpdateSemaphore(r) => r.req_id = id,
+        Request::DeleteSemaphore(r) => r.req_id = id,
+        _ => {}
+    }
+}
+
+fn req_id(response: &Response) -> Option<u64> {
+    match response {
+        Response::AcquireSemaphoreResult(r) => Some(r.req_id),
+        Response::ReleaseSemaphoreResult(r) => Some(r.req_id),
+        Response::DescribeSemaphoreResult(r) => Some(r.req_id),
+        Response::CreateSemaphoreResult(r) => Some(r.req_id),
+        Response::UpdateSemaphoreResult(r) => Some(r.req_id),
+        Response::DeleteSemaphoreResult(r) => Some(r.req_id),
+        _ => None,
+    }
+}
+
+fn session_request(request: Request) -> SessionRequest {
+    SessionRequest { request: Some(request) }
+}
+
+struct Connection {
+    sender: UnboundedSender<SessionRequest>,
+    responses: Streaming<SessionResponse>,
+}
+
+impl Connection {
+    fn send(&self, request: Request) {
+        //if stream is broken, error will be received from responses
+        let _ = self.sender.unbounded_send(session_request(request));
+    }
+}
+
+enum Served {
+    Stopped(Option<oneshot::Sender<()>>),
+    Broken(YdbError),
+}
+
+struct Actor<C: Credentials> {
+    client: CoordinationServiceClient<YdbService<C>>,
+    path: String,
+    protection_key: Vec<u8>,
+    options: SessionOptions,
+    session_id: u64,
+    seq_no: u64,
+    next_req_id: u64,
+    pending: HashMap<u64, Reply>,
+    watchers: HashMap<u64, oneshot::Sender<resp::DescribeSemaphoreChanged>>,
+    state: watch::Sender<SessionState>,
+}
+
+impl<C: Credentials> Actor<C> {
+    /// Starts new session or restores existing one (if `session_id` is set)
+    async fn connect(&mut self) -> Result<Connection, YdbError> {
+        self.seq_no += 1;
+        let (sender, receiver) = unbounded();
+        let start = req::SessionStart {
+            path: self.path.clone(),
+            session_id: self.session_id,
+            timeout_millis: self.options.timeout.as_millis() as u64,
+            description: self.options.description.clone(),
+            seq_no: self.seq_no,
+            protection_key: self.protection_key.clone(),
+        };
+        let _ = sender.unbounded_send(session_request(Request::SessionStart(start)));
+        let mut responses = self.client.session(receiver).await?.into_inner();
+        while let Some(response) = responses.message().await? {
+            match response.response {
+                Some(Response::SessionStarted(started)) => {
+                    self.session_id = started.session_id;
+                    return Ok(Connection { sender, responses });
+                }
+                Some(Response::Failure(failure)) => {
+                    return Err(YdbError::Ydb(ErrWithOperation::from_status(failure.status, failure.issues)));
+                }
+                Some(Response::Ping(ping)) => {
+                    let _ = sender.unbounded_send(session_request(Request::Pong(req::PingPong { opaque: ping.opaque })));
+                }
+                _ => {}
+            }
+        }
+        Err(YdbError::EmptyResponse)
+    }
+    async fn run(mut self, mut connection: Connection, mut commands: mpsc::UnboundedReceiver<Command>) {
+        loop {
+            match self.serve(&mut connection, &mut commands).await {
+                Served::Stopped(notify) => {
+                    log::debug!("Coordination session stopped: {}", self.session_id);
+                    self.state.send_replace(SessionState::Lost);
+                    if let Some(notify) = notify {
+                        let _ = notify.send(());
+                    }
+                    return;
+                }
+                Served::Broken(e) => {
+                    log::warn!("Coordination session {} disconnected: {e}", self.session_id);
+                    self.fail_pending();
+                    self.state.send_replace(SessionState::Reconnecting);
+                    if let Some(restored) = self.reconnect().await {
+                        connection = restored;
+                        self.state.send_replace(SessionState::Connected);
+                    } else {
+                        log::error!("Coordination session lost: {}", self.session_id);
+                        self.state.send_replace(SessionState::Lost);
+                        return;
+                    }
+                }
+            }
+        }
+    }
+    async fn serve(&mut self, connection: &mut Connection, commands: &mut mpsc::UnboundedReceiver<Command>) -> Served {
+        loop {
+            tokio::select! {
+                command = commands.recv() => match command {
+                    Some(Command::Request { mut request, reply, changed }) => {
+                        let req_id = self.next_req_id;
+                        self.next_req_id += 1;
+                        set_req_id(&mut request, req_id);
+                        if let Some(reply) = reply {
+                            self.pending.insert(req_id, reply);
+                        }
+                        if let Some(changed) = changed {
+                            self.watchers.insert(req_id, changed);
+                        }
+                        connection.send(request);
+                    }
+                    Some(Command::Stop(notify)) => {
+                        self.stop(connection).await;
+                        return Served::Stopped(Some(notify));
+                    }
+                    None => {
+                        self.stop(connection).await;
+                        return Served::Stopped(None);
+                    }
+                },
+                response = connection.responses.message() => match response {
+                    Ok(Some(SessionResponse { response: Some(response) })) => match response {
+                        Response::Ping(ping) => connection.send(Request::Pong(req::PingPong { opaque: ping.opaque })),
+                        Response::Failure(failure) => {
+                            let e = YdbError::Ydb(ErrWithOperation::from_status(failure.status, failure.issues));
+                            return Served::Broken(e);
+                        }
+                        Response::SessionStopped(_) => return Served::Stopped(None),
+                        Response::AcquireSemaphorePending(pending) => {
+                            log::debug!("Semaphore acquiring is pending (request {})", pending.req_id);
+                        }
+                        Response::DescribeSemaphoreChanged(changed) => {
+                            if let Some(watcher) = self.watchers.remove(&changed.req_id) {
+                                let _ = watcher.send(changed);
+                            }
+                        }
+                        response => if let Some(reply) = req_id(&response).and_then(|id| self.pending.remove(&id)) {
+                            let _ = reply.send(Ok(response));
+                        }
+                    },
+                    Ok(Some(_)) => {}
+                    Ok(None) => return Served::Broken(YdbError::EmptyResponse),
+                    Err(status) => return Served::Broken(status.into()),
+                }
+            }
+        }
+    }
+    fn fail_pending(&mut self) {
+        for (_, reply) in self.pending.drain() {
+            let _ = reply.send(Err(tonic::Status::unavailable("coordination session disconnected").into()));
+        }
+        //receivers of watchers get error and have to describe semaphore again
+        self.watchers.clear();
+    }
+    /// Tries to restore session until its timeout exceeded. Returns `None` if session cannot be restored
+    async fn reconnect(&mut self) -> Option<Connection> {
+        let deadline = std::time::Instant::now() + self.options.timeout;
+        loop {
+            tokio::time::sleep(self.options.reconnect_interval).await;
+            match self.connect().await {
+                Ok(connection) => {
+                    log::debug!("Coordination session restored: {}", self.session_id);
+                    return Some(connection);
+                }
+                Err(YdbError::Ydb(ErrWithOperation(operation))) if matches!(
+                    operation.status(), StatusCode::BadSession | StatusCode::SessionExpired | StatusCode::NotFound
+                ) => return None,
+                Err(e) if std::time::Instant::now() > deadline => {
+                    log::error!("Cannot restore coordination session {}: {e}", self.session_id);
+                    return None;
+                }
+                Err(e) => log::warn!("Cannot restore coordination session {}: {e}", self.session_id),
+            }
+        }
+    }
+    async fn stop(&mut self, connection: &mut Connection) {
+        connection.send(Request::SessionStop(req::SessionStop {}));
+        let wait = async {
+            while let Ok(Some(response)) = connection.responses.message().await {
+                if let Some(Response::SessionStopped(_)) = response.response {
+                    break;
+                }
+            }
+        };
+        if tokio::time::timeout(self.options.timeout, wait).await.is_err() {
+            log::warn!("Coordination session {} is not stopped gracefully", self.session_id);
+        }
+    }
+}
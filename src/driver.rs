@@ -0,0 +1,214 @@
+//! Driver with client-side balancing. It discovers nodes of database with `ListEndpoints`, keeps channels to each node
+//! (one by default, see [`DriverOptions::channels_per_endpoint`]) and periodically refreshes list of nodes. Each [`YdbConnection`], created by driver, is bound to one node
+//! (chosen by `load_factor`), so its session and all session-bound requests go to the node, that owns the session.
+//!
+//! Routing is not automatic for sessions, that are passed between connections: requests with session of another
+//! connection go to node of current connection. Use [`Driver::connection_for_session`] explicitly to get connection
+//! to node, that owns the session.
+//!
+//! # Examples
+//! ```rust,no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//!     use ydb_unofficial::client::YdbEndpoint;
+//!     use ydb_unofficial::driver::{Driver, DriverOptions};
+//!     let db_name = std::env::var("DB_NAME").expect("DB_NAME not set");
+//!     let creds = std::env::var("DB_TOKEN").expect("DB_TOKEN not set");
+//!     let endpoint = YdbEndpoint { ssl: true, host: "ydb.serverless.yandexcloud.net".to_owned(), port: 2135, load_factor: 0.0 };
+//!     let driver = Driver::new(creds, db_name.as_str().try_into().unwrap(), endpoint, DriverOptions::default()).await.unwrap();
+//!     let mut conn = driver.connection();
+//!     let table = conn.table().await.unwrap();
+//! # }
+//! ```
+use std::sync::{Arc, RwLock, Weak};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use rand::Rng;
+use tonic::transport::Channel;
+
+use super::*;
+use auth::Credentials;
+use client::YdbEndpoint;
+use error::YdbError;
+use payload::YdbResponseWithResult;
+
+use generated::ydb::discovery::{EndpointInfo, ListEndpointsRequest};
+
+/// Settings of [`Driver`]
+#[derive(Debug, Clone)]
+pub struct DriverOptions {
+    /// How often list of nodes is refreshed
+    pub discovery_interval: Duration,
+    /// Count of grpc channels (HTTP/2 connections) per node. Connections to node share these channels
+    pub channels_per_endpoint: usize,
+}
+
+impl Default for DriverOptions {
+    fn default() -> Self {
+        Self { discovery_interval: Duration::from_secs(60), channels_per_endpoint: 1 }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    /// Zero for initial endpoint (before discovery)
+    node_id: u32,
+    endpoint: YdbEndpoint,
+    /// Channels connect lazily, on first request
+    channels: Vec<Channel>,
+}
+
+impl Node {
+    fn new(node_id: u32, endpoint: YdbEndpoint, channels: usize) -> Self {
+        let channels = (0..channels.max(1)).map(|_| endpoint.make_endpoint().connect_lazy()).collect();
+        Self { node_id, endpoint, channels }
+    }
+}
+
+/// Replaces list of nodes with discovered ones, keeping channels of nodes, that are still present
+fn merge_nodes(old: &[Node], infos: Vec<EndpointInfo>, channels: usize) -> Vec<Node> {
+    infos.into_iter().map(|info| {
+        let node_id = info.node_id;
+        let endpoint = YdbEndpoint::from(info);
+        match old.iter().find(|n| n.endpoint.same_address(&endpoint)) {
+            Some(known) => Node { node_id, endpoint, channels: known.channels.clone() },
+            None => Node::new(node_id, endpoint, channels),
+        }
+    }).collect()
+}
+
+#[derive(Debug)]
+struct Shared<C: Credentials> {
+    db_name: AsciiValue,
+    creds: C,
+    options: DriverOptions,
+    nodes: RwLock<Vec<Node>>,
+    /// Counter for round robin between channels of node
+    next_channel: AtomicUsize,
+}
+
+/// Shared driver of database. Cheap to clone: clones share the same channels.
+/// Refreshing of nodes stops when all clones are dropped
+#[derive(Debug, Clone)]
+pub struct Driver<C: Credentials> {
+    shared: Arc<Shared<C>>,
+}
+
+impl<C: Credentials + Sync> Driver<C> {
+    /// Discovers nodes of database through `endpoint` and starts background refreshing of them
+    pub async fn new(creds: C, db_name: AsciiValue, endpoint: YdbEndpoint, options: DriverOptions) -> Result<Self, YdbError> {
+        let driver = Self::create(creds, db_name, endpoint, options);
+        driver.discover().await?;
+        spawn_discovery(Arc::downgrade(&driver.shared), driver.shared.options.discovery_interval);
+        Ok(driver)
+    }
+    /// Like [`Driver::new`], but does not wait for discovery: connections go to `endpoint` until nodes are discovered
+    /// in background. Must be called inside tokio runtime
+    pub fn new_lazy(creds: C, db_name: AsciiValue, endpoint: YdbEndpoint, options: DriverOptions) -> Self {
+        let driver = Self::create(creds, db_name, endpoint, options);
+        spawn_discovery(Arc::downgrade(&driver.shared), Duration::ZERO);
+        driver
+    }
+    fn create(creds: C, db_name: AsciiValue, endpoint: YdbEndpoint, options: DriverOptions) -> Self {
+        let nodes = RwLock::new(vec![Node::new(0, endpoint, options.channels_per_endpoint)]);
+        Self { shared: Arc::new(Shared { db_name, creds, options, nodes, next_channel: AtomicUsize::new(0) }) }
+    }
+    /// Refreshes list of nodes. Channels of known nodes are reused
+    pub async fn discover(&self) -> Result<(), YdbError> {
+        let database = self.shared.db_name.to_str().unwrap_or_default().to_owned();
+        let mut conn = self.connection();
+        let response = conn.discovery().list_endpoints(ListEndpointsRequest { database, ..Default::default() }).await?;
+        let infos = response.into_inner().result()?.endpoints;
+        if infos.is_empty() {
+            log::warn!("Discovery returned no endpoints, keeping previous ones");
+            return Ok(());
+        }
+        let mut nodes = self.shared.nodes.write().unwrap();
+        let updated = merge_nodes(&nodes, infos, self.shared.options.channels_per_endpoint);
+        log::debug!("Driver endpoints updated ({} endpoints)", updated.len());
+        *nodes = updated;
+        Ok(())
+    }
+    /// Current list of nodes
+    pub fn endpoints(&self) -> Vec<YdbEndpoint> {
+        self.shared.nodes.read().unwrap().iter().map(|n| n.endpoint.clone()).collect()
+    }
+    /// Creates connection to one of nodes. Node is chosen by `load_factor` from two random nodes
+    pub fn connection(&self) -> YdbConnection<C> {
+        let nodes = self.shared.nodes.read().unwrap();
+        self.connect(&nodes[pick(&nodes, |n| n.endpoint.load_factor)])
+    }
+    /// Creates connection to node, that owns session (node id is a part of session id).
+    /// Use it to continue work with session, created by another connection
+    pub fn connection_for_session(&self, session_id: &str) -> YdbConnection<C> {
+        let nodes = self.shared.nodes.read().unwrap();
+        match node_id_of(session_id).and_then(|id| nodes.iter().find(|n| n.node_id == id)) {
+            Some(node) => self.connect(node),
+            None => self.connect(&nodes[pick(&nodes, |n| n.endpoint.load_factor)]),
+        }
+    }
+    fn connect(&self, node: &Node) -> YdbConnection<C> {
+        let next = self.shared.next_channel.fetch_add(1, Ordering::Relaxed);
+        let channel = node.channels[next % node.channels.len()].clone();
+        YdbConnection::new(channel, self.shared.db_name.clone(), self.shared.creds.clone()).with_endpoint(node.endpoint.clone())
+    }
+}
+
+/// Refreshes nodes after `delay` and then every `discovery_interval`
+fn spawn_discovery<C: Credentials + Sync>(shared: Weak<Shared<C>>, mut delay: Duration) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(delay).await;
+            let Some(shared) = shared.upgrade() else { break };
+            delay = shared.options.discovery_interval;
+            if let Err(e) = (Driver { shared }).discover().await {
+                log::error!("Error on update endpoints for driver: {e}");
+            }
+        }
+        log::debug!("Discovery of driver stopped");
+    });
+}
+
+/// Chooses index of less loaded item of two random ones. Used by pool of connections too
+pub(crate) fn pick<T>(items: &[T], load_factor: impl Fn(&T) -> f32) -> usize {
+    let len = items.len();
+    assert!(len > 0, "List of endpoints is empty");
+    if len == 1 {
+        return 0;
+    }
+    let mut rng = rand::thread_rng();
+    let first = rng.gen_range(0..len);
+    let mut second = rng.gen_range(0..len - 1);
+    if second >= first {
+        second += 1;
+    }
+    if load_factor(&items[first]) <= load_factor(&items[second]) { first } else { second }
+}
+
+/// Parses node id from session id like `ydb://session/3?node_id=50001&id=...`
+fn node_id_of(session_id: &str) -> Option<u32> {
+    let (_, query) = session_id.split_once('?')?;
+    query.split('&').find_map(|pair| pair.strip_prefix("node_id=")).and_then(|id| id.parse().ok())
+}
+
+#[tokio::test]
+async fn balancing_and_routing() {
+    let endpoint = |host: &str, load_factor| YdbEndpoint { ssl: false, host: host.to_owned(), port: 2135, load_factor };
+    let nodes = vec![Node::new(1, endpoint("a", 0.9), 1), Node::new(2, endpoint("b", 0.1), 1)];
+    for _ in 0..10 {
+        assert_eq!(pick(&nodes, |n| n.endpoint.load_factor), 1);
+    }
+    assert!(nodes[0].endpoint.same_address(&endpoint("a", 0.5)));
+    assert_eq!(node_id_of("ydb://session/3?node_id=50001&id=NjZkNT"), Some(50001));
+    assert_eq!(node_id_of("some-session"), None);
+}
+
+#[tokio::test]
+async fn merge_keeps_channels() {
+    let info = |host: &str, node_id| EndpointInfo { address: host.to_owned(), port: 2135, node_id, ..Default::default() };
+    let old = vec![Node::new(1, YdbEndpoint::from(info("a", 1)), 2), Node::new(2, YdbEndpoint::from(info("b", 2)), 2)];
+    let merged = merge_nodes(&old, vec![info("c", 3), info("a", 4)], 1);
+    let nodes: Vec<_> = merged.iter().map(|n| (n.endpoint.host.as_str(), n.node_id, n.channels.len())).collect();
+    assert_eq!(nodes, [("c", 3, 1), ("a", 4, 2)]);
+}
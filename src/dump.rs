@@ -0,0 +1,183 @@
+//! Local dump and restore of tables.
+//!
+//! Each table is saved to its own directory (path of table relative to database): schema goes to `scheme.pb`
+//! (encoded [`DescribeTableResult`]), data goes to `data_00000.pb`, `data_00001.pb`... files with
+//! length-delimited [`ResultSet`] messages. Data is read from consistent snapshot of table.
+//!
+//! # Examples
+//! ```rust,no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//!     use ydb_unofficial::dump::{dump, restore, DumpOptions, RestoreOptions};
+//!     let mut conn = ydb_unofficial::YdbConnection::from_env();
+//!     dump(&mut conn, &["users", "orders/items"], "backup".as_ref(), DumpOptions::default()).await.unwrap();
+//!     let options = RestoreOptions { prefix: "copy".to_owned(), ..Default::default() };
+//!     let tables = restore(&mut conn, "backup".as_ref(), options).await.unwrap();
+//!     println!("restored: {tables:?}");
+//! # }
+//! ```
+use std::path::{Path, PathBuf};
+
+use prost::Message;
+
+use super::*;
+use auth::Credentials;
+use client::YdbConnection;
+use error::{YdbError, check_status};
+use bulk::{upsert_all, rows_value};
+use payload::{ExtractResultError, YdbResponseWithResult};
+
+use generated::ydb::ResultSet;
+use generated::ydb::feature_flag::Status as FeatureFlag;
+use generated::ydb::table::{DescribeTableRequest, DescribeTableResult, CreateTableRequest, ReadTableRequest, BulkUpsertRequest};
+use generated::ydb::table::{TableIndex, TableIndexDescription, table_index, table_index_description};
+
+const SCHEME_FILE: &str = "scheme.pb";
+
+/// Settings of [`dump`]
+#[derive(Debug, Clone)]
+pub struct DumpOptions {
+    /// Data file is closed and next one is started, when file exceeds this size
+    pub file_size: usize,
+}
+
+impl Default for DumpOptions {
+    fn default() -> Self {
+        Self { file_size: 64 * 1024 * 1024 }
+    }
+}
+
+/// Settings of [`restore`]
+#[derive(Debug, Clone)]
+pub struct RestoreOptions {
+    /// Directory of database, where tables will be created (root of database if empty)
+    pub prefix: String,
+    /// Max rows in one `BulkUpsert` request
+    pub batch_rows: usize,
+    /// Count of `BulkUpsert` requests in parallel
+    pub concurrency: usize,
+}
+
+impl Default for RestoreOptions {
+    fn default() -> Self {
+        Self { prefix: String::new(), batch_rows: 10_000, concurrency: 4 }
+    }
+}
+
+fn data_file(dir: &Path, number: usize) -> PathBuf {
+    dir.join(format!("data_{number:05}.pb"))
+}
+
+/// Saves schema and data of tables to directory `to`
+pub async fn dump<C: Credentials + Send>(conn: &mut YdbConnection<C>, tables: &[&str], to: &Path, options: DumpOptions) -> Result<(), YdbError> {
+    for table in tables {
+        let dir = to.join(table.trim_start_matches('/'));
+        tokio::fs::create_dir_all(&dir).await?;
+        let mut client = conn.table().await?;
+        let response = client.describe_table(DescribeTableRequest { path: table.to_string(), ..Default::default() }).await?;
+        let scheme = response.into_inner().result()?;
+        tokio::fs::write(dir.join(SCHEME_FILE), scheme.encode_to_vec()).await?;
+        let req = ReadTableRequest { path: table.to_string(), use_snapshot: FeatureFlag::Enabled.into(), ..Default::default() };
+        let mut parts = client.stream_read_table(req).await?.into_inner();
+        let mut buffer = Vec::new();
+        let mut files = 0;
+        let mut rows = 0;
+        while let Some(part) = parts.message().await? {
+            check_status(part.status, part.issues)?;
+            let Some(result_set) = part.result.and_then(|r| r.result_set) else { continue };
+            rows += result_set.rows.len();
+            buffer.extend(result_set.encode_length_delimited_to_vec());
+            if buffer.len() >= options.file_size {
+                tokio::fs::write(data_file(&dir, files), std::mem::take(&mut buffer)).await?;
+                files += 1;
+            }
+        }
+        if !buffer.is_empty() {
+            tokio::fs::write(data_file(&dir, files), buffer).await?;
+        }
+        log::debug!("Table {table} is dumped: {rows} rows");
+    }
+    Ok(())
+}
+
+fn table_index(value: TableIndexDescription) -> TableIndex {
+    let r#type = value.r#type.map(|t| match t {
+        table_index_description::Type::GlobalIndex(i) => table_index::Type::GlobalIndex(i),
+        table_index_description::Type::GlobalAsyncIndex(i) => table_index::Type::GlobalAsyncIndex(i),
+    });
+    TableIndex { name: value.name, index_columns: value.index_columns, data_columns: value.data_columns, r#type }
+}
+
+/// Request to create table with the same schema as described one
+fn create_request(path: String, scheme: DescribeTableResult) -> CreateTableRequest {
+    CreateTableRequest {
+        path,
+        columns: scheme.columns,
+        primary_key: scheme.primary_key,
+        indexes: scheme.indexes.into_iter().map(table_index).collect(),
+        ttl_settings: scheme.ttl_settings,
+        storage_settings: scheme.storage_settings,
+        column_families: scheme.column_families,
+        attributes: scheme.attributes,
+        partitioning_settings: scheme.partitioning_settings,
+        key_bloom_filter: scheme.key_bloom_filter,
+        read_replicas_settings: scheme.read_replicas_settings,
+        ..Default::default()
+    }
+}
+
+/// Finds dumped tables (directories with schema file) in `dir`. Returns relative paths of tables
+async fn find_tables(dir: &Path) -> Result<Vec<PathBuf>, YdbError> {
+    let mut tables = Vec::new();
+    let mut dirs = vec![PathBuf::new()];
+    while let Some(relative) = dirs.pop() {
+        let mut entries = tokio::fs::read_dir(dir.join(&relative)).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if entry.file_type().await?.is_dir() {
+                dirs.push(relative.join(entry.file_name()));
+            } else if entry.file_name() == SCHEME_FILE {
+                tables.push(relative.clone());
+            }
+        }
+    }
+    tables.sort();
+    Ok(tables)
+}
+
+/// Creates tables, dumped to directory `from`, and loads their data. Returns paths of created tables
+pub async fn restore<C: Credentials + Send>(conn: &mut YdbConnection<C>, from: &Path, options: RestoreOptions) -> Result<Vec<String>, YdbError> {
+    let mut restored = Vec::new();
+    for relative in find_tables(from).await? {
+        let dir = from.join(&relative);
+        let relative = relative.iter().map(|p| p.to_string_lossy()).collect::<Vec<_>>().join("/");
+        let table = if options.prefix.is_empty() { relative } else { format!("{}/{relative}", options.prefix.trim_end_matches('/')) };
+        let scheme = DescribeTableResult::decode(tokio::fs::read(dir.join(SCHEME_FILE)).await?.as_slice()).map_err(ExtractResultError::from)?;
+        conn.table().await?.create_table(create_request(table.clone(), scheme)).await?;
+        let mut files = 0;
+        let mut rows = 0;
+        loop {
+            let data = match tokio::fs::read(data_file(&dir, files)).await {
+                Ok(data) => data,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => break,
+                Err(e) => return Err(e.into()),
+            };
+            let mut data = data.as_slice();
+            let mut batches = Vec::new();
+            while !data.is_empty() {
+                let ResultSet { columns, rows: result_rows, .. } = ResultSet::decode_length_delimited(&mut data).map_err(ExtractResultError::from)?;
+                let mut result_rows = result_rows.into_iter().peekable();
+                while result_rows.peek().is_some() {
+                    let batch: Vec<_> = result_rows.by_ref().take(options.batch_rows.max(1)).collect();
+                    let count = batch.len();
+                    let req = BulkUpsertRequest { table: table.clone(), rows: Some(rows_value(&columns, batch)), ..Default::default() };
+                    batches.push(Ok((count, req)));
+                }
+            }
+            rows += upsert_all(conn.service(), batches.into_iter(), options.concurrency).await?;
+            files += 1;
+        }
+        log::debug!("Table {table} is restored: {rows} rows");
+        restored.push(table);
+    }
+    Ok(restored)
+}
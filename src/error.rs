@@ -4,6 +4,7 @@ use std::fmt::Display;
 use thiserror::Error;
 
 use crate::generated::ydb::operations::Operation;
+use crate::generated::ydb::issue::IssueMessage;
 pub use crate::payload::ExtractResultError;
 
 #[derive(Error, Debug)]
@@ -15,6 +16,23 @@ pub enum YdbError {
     Ydb(ErrWithOperation),
     #[error("Empty response")]
     EmptyResponse,
+    #[error("Operation {0} is not ready after timeout")]
+    OperationTimeout(String),
+    #[error("Session is closed")]
+    SessionClosed,
+    #[error("No free session in pool after timeout")]
+    SessionPoolTimeout,
+    #[error("Codec error: {0}")]
+    Codec(String),
+    Io(#[from] std::io::Error),
+    #[cfg(feature = "cdc")]
+    #[error("Invalid change record: {0}")]
+    ChangeRecord(String),
+    #[error("Invalid input data: {0}")]
+    InvalidInput(String),
+    #[cfg(feature = "pool")]
+    #[error("Cannot get connection from pool: {0}")]
+    Pool(#[from] deadpool::managed::PoolError<tonic::transport::Error>),
     #[cfg(feature = "sqlx")]
     #[error("Error on decode ast")]
     DecodeAst,
@@ -26,9 +44,32 @@ pub enum YdbError {
 #[derive(Error, Debug)]
 pub struct ErrWithOperation(pub Operation);
 
+impl ErrWithOperation {
+    /// Wraps status of streaming response part (it has no operation) into completed [`Operation`]
+    pub(crate) fn from_status(status: i32, issues: Vec<IssueMessage>) -> Self {
+        Self(Operation { ready: true, status, issues, ..Default::default() })
+    }
+}
+
+/// Checks status of response, that has no operation (e.g. streaming responses)
+pub(crate) fn check_status(status: i32, issues: Vec<IssueMessage>) -> Result<(), YdbError> {
+    use crate::generated::ydb::status_ids::StatusCode;
+    match StatusCode::from_i32(status) {
+        Some(StatusCode::Success) => Ok(()),
+        _ => Err(YdbError::Ydb(ErrWithOperation::from_status(status, issues))),
+    }
+}
+
 impl Display for ErrWithOperation {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let status = self.0.status();
-        write!(f, "Operation status: {status:?}")
+        write!(f, "Operation status: {status:?}")?;
+        if !self.0.id.is_empty() {
+            write!(f, ", id: {}", self.0.id)?;
+        }
+        for issue in &self.0.issues {
+            write!(f, "; {}", issue.message)?;
+        }
+        Ok(())
     }
 }
\ No newline at end of file
@@ -0,0 +1,116 @@
+//! Secondary index lifecycle: add (with async build), drop and rename indexes of table.
+//!
+//! # Examples
+//! ```rust,no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//!     use futures::StreamExt;
+//!     use ydb_unofficial::index::{IndexDefinition, build_progress};
+//!     use ydb_unofficial::operation::WaitPolicy;
+//!     let mut conn = ydb_unofficial::YdbConnection::from_env();
+//!     let index = IndexDefinition::sync("idx_by_name", ["name"]).covering(["age"]);
+//!     let operation = conn.table().await.unwrap().add_index("users", index).await.unwrap();
+//!     let mut progress = std::pin::pin!(build_progress(conn.operation().watch(operation, WaitPolicy::default())));
+//!     while let Some(metadata) = progress.next().await {
+//!         let metadata = metadata.unwrap();
+//!         println!("{:?}: {}%", metadata.state(), metadata.progress);
+//!     }
+//! # }
+//! ```
+use futures::{Stream, StreamExt};
+
+use super::*;
+use auth::Credentials;
+use client::TableClientWithSession;
+use error::YdbError;
+use payload::ExtractResultError;
+use operation::{async_params, started};
+
+use generated::ydb::operations::Operation;
+use generated::ydb::table::{AlterTableRequest, TableIndex, GlobalIndex, GlobalAsyncIndex, RenameIndexItem, IndexBuildMetadata};
+use generated::ydb::table::table_index::Type as IndexType;
+use generated::ydb::table::index_build_state::State;
+
+/// Description of secondary index to add
+#[derive(Debug, Clone)]
+pub struct IndexDefinition {
+    name: String,
+    columns: Vec<String>,
+    data_columns: Vec<String>,
+    is_async: bool,
+}
+
+impl IndexDefinition {
+    fn new<S: ToString>(name: &str, columns: impl IntoIterator<Item = S>, is_async: bool) -> Self {
+        let columns = columns.into_iter().map(|c|c.to_string()).collect();
+        Self { name: name.to_owned(), columns, data_columns: vec![], is_async }
+    }
+    /// Global index, that updates synchronously with table
+    pub fn sync<S: ToString>(name: &str, columns: impl IntoIterator<Item = S>) -> Self {
+        Self::new(name, columns, false)
+    }
+    /// Global index, that updates asynchronously (faster writes, but reads by index may be stale)
+    pub fn asynchronous<S: ToString>(name: &str, columns: impl IntoIterator<Item = S>) -> Self {
+        Self::new(name, columns, true)
+    }
+    /// Makes covering index: columns will be copied to index to read them without access to table
+    pub fn covering<S: ToString>(mut self, data_columns: impl IntoIterator<Item = S>) -> Self {
+        self.data_columns = data_columns.into_iter().map(|c|c.to_string()).collect();
+        self
+    }
+}
+
+impl From<IndexDefinition> for TableIndex {
+    fn from(value: IndexDefinition) -> Self {
+        let IndexDefinition { name, columns, data_columns, is_async } = value;
+        let index_type = if is_async {
+            IndexType::GlobalAsyncIndex(GlobalAsyncIndex {})
+        } else {
+            IndexType::GlobalIndex(GlobalIndex {})
+        };
+        Self { name, index_columns: columns, data_columns, r#type: Some(index_type) }
+    }
+}
+
+impl<'a, C: Credentials + Send> TableClientWithSession<'a, C> {
+    /// Starts to build index in async mode. Returns operation of index building,
+    /// use [`crate::operation::OperationClient`] to wait it or to watch progress (see [`build_progress`])
+    pub async fn add_index(&mut self, table: &str, index: impl Into<TableIndex>) -> Result<Operation, YdbError> {
+        let req = AlterTableRequest {
+            path: table.to_owned(),
+            add_indexes: vec![index.into()],
+            operation_params: Some(async_params()),
+            ..Default::default()
+        };
+        let response = started(self.alter_table(req).await)?;
+        response.operation.ok_or(YdbError::EmptyResponse)
+    }
+    /// Drops index of table
+    pub async fn drop_index(&mut self, table: &str, name: &str) -> Result<(), YdbError> {
+        let req = AlterTableRequest { path: table.to_owned(), drop_indexes: vec![name.to_owned()], ..Default::default() };
+        self.alter_table(req).await?;
+        Ok(())
+    }
+    /// Renames index of table. If `replace` is set, existing index with destination name will be replaced
+    pub async fn rename_index(&mut self, table: &str, from: &str, to: &str, replace: bool) -> Result<(), YdbError> {
+        let rename = RenameIndexItem { source_name: from.to_owned(), destination_name: to.to_owned(), replace_destination: replace };
+        let req = AlterTableRequest { path: table.to_owned(), rename_indexes: vec![rename], ..Default::default() };
+        self.alter_table(req).await?;
+        Ok(())
+    }
+}
+
+/// Decodes metadata of index building operation
+pub fn build_metadata(operation: &Operation) -> Result<IndexBuildMetadata, ExtractResultError> {
+    match &operation.metadata {
+        Some(metadata) => Ok(prost::Message::decode(metadata.value.as_slice())?),
+        //ready operation may have no metadata
+        None if operation.ready => Ok(IndexBuildMetadata { state: State::Done.into(), progress: 100.0, ..Default::default() }),
+        None => Err(ExtractResultError::Empty),
+    }
+}
+
+/// Converts stream of operation states (see [`crate::operation::OperationClient::watch`]) to stream of index building progress
+pub fn build_progress<'a>(operations: impl Stream<Item = Result<Operation, YdbError>> + 'a) -> impl Stream<Item = Result<IndexBuildMetadata, YdbError>> + 'a {
+    operations.map(|operation| Ok(build_metadata(&operation?)?))
+}
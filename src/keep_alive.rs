@@ -0,0 +1,228 @@
+//! Background keep-alive of sessions. Idle sessions (e.g. on serverless databases) silently expire,
+//! so background task periodically checks them with `KeepAlive` request. Bad sessions are replaced with new ones,
+//! so the first query after quiet period doesn't fail with `BadSession`. Busy sessions of pool are replaced too,
+//! but busy session of connection is kept: it may execute query of this connection right now.
+//!
+//! Keep-alive is available for [`crate::YdbConnection`] (see [`crate::YdbConnection::with_keep_alive`]),
+//! for [`crate::session_pool::SessionPool`], for pool of connections (feature `pool`) and for sqlx connections (feature `sqlx`)
+//!
+//! # Examples
+//! ```rust,no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//!     use std::time::Duration;
+//!     use ydb_unofficial::keep_alive::KeepAliveOptions;
+//!     let options = KeepAliveOptions { interval: Duration::from_secs(30), ..Default::default() };
+//!     let mut conn = ydb_unofficial::YdbConnection::from_env().with_keep_alive(options);
+//!     let table = conn.table().await.unwrap();
+//! # }
+//! ```
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::time::{Duration, Instant};
+
+use super::*;
+use auth::Credentials;
+use client::{YdbService, has_session_close_hint};
+use payload::YdbResponseWithResult;
+use session_pool::{Shared, SessionPool, IdleSession, create_session, spawn_delete};
+
+use generated::ydb::status_ids::StatusCode;
+use generated::ydb::table::{KeepAliveRequest, KeepAliveResponse};
+use generated::ydb::table::keep_alive_result::SessionStatus;
+use generated::ydb::table::v1::table_service_client::TableServiceClient;
+
+/// Settings of background keep-alive
+#[derive(Debug, Clone)]
+pub struct KeepAliveOptions {
+    /// How often sessions are checked
+    pub interval: Duration,
+    /// Sessions, that are not used (or checked) for this time, are checked with `KeepAlive`
+    pub idle_time: Duration,
+    /// Sessions of pool, that are not used for this time, are deleted (but pool keeps `min_sessions`)
+    pub evict_after: Option<Duration>,
+}
+
+impl Default for KeepAliveOptions {
+    fn default() -> Self {
+        Self { interval: Duration::from_secs(30), idle_time: Duration::from_secs(60), evict_after: None }
+    }
+}
+
+/// Result of keep-alive of one session
+#[derive(Debug, PartialEq)]
+enum Check {
+    Ready,
+    /// Session is busy or bad, it must be replaced
+    Replace { busy: bool },
+    /// Session state is unknown (e.g. network error), it is kept as is
+    Unknown,
+}
+
+async fn check<C: Credentials>(service: YdbService<C>, session_id: String) -> Check {
+    let mut client = TableServiceClient::new(service);
+    match client.keep_alive(KeepAliveRequest { session_id: session_id.clone(), ..Default::default() }).await {
+        //node is going to shut down, session must not be used anymore
+        Ok(response) if has_session_close_hint(response.metadata()) => Check::Replace { busy: false },
+        Ok(response) => check_response(response.into_inner(), &session_id),
+        Err(e) if has_session_close_hint(e.metadata()) => Check::Replace { busy: false },
+        Err(e) => {
+            log::warn!("Keep-alive of session {session_id} failed: {e}");
+            Check::Unknown
+        }
+    }
+}
+
+fn check_response(response: KeepAliveResponse, session_id: &str) -> Check {
+    match response.operation.as_ref().map(|o| o.status()) {
+        Some(StatusCode::Success) => match response.result() {
+            Ok(result) if result.session_status() == SessionStatus::Busy => Check::Replace { busy: true },
+            Ok(_) => Check::Ready,
+            Err(e) => {
+                log::warn!("Keep-alive of session {session_id} failed: {e}");
+                Check::Unknown
+            }
+        },
+        Some(StatusCode::BadSession | StatusCode::SessionExpired) => Check::Replace { busy: false },
+        Some(StatusCode::SessionBusy) => Check::Replace { busy: true },
+        status => {
+            log::warn!("Keep-alive of session {session_id} failed with status {status:?}");
+            Check::Unknown
+        }
+    }
+}
+
+/// Keeps alive session of connection until connection is dropped.
+/// Busy session is kept: it may execute query of this connection right now
+pub(crate) fn spawn_for_connection<C: Credentials>(
+    service: YdbService<C>,
+    session_ref: Weak<RwLock<Option<String>>>,
+    last_used: Arc<Mutex<Instant>>,
+    options: KeepAliveOptions,
+) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(options.interval).await;
+            let Some(session_ref) = session_ref.upgrade() else { break };
+            if last_used.lock().unwrap().elapsed() < options.idle_time {
+                continue;
+            }
+            let Some(session_id) = session_ref.read().unwrap().clone() else { continue };
+            let Check::Replace { busy: false } = check(service.clone(), session_id.clone()).await else { continue };
+            log::debug!("Session {session_id} is bad, replacing it");
+            match create_session(service.clone()).await {
+                Ok(new_id) => {
+                    let mut current = session_ref.write().unwrap();
+                    if current.as_ref() == Some(&session_id) {
+                        *current = Some(new_id);
+                    } else {
+                        spawn_delete(service.clone(), new_id);
+                    }
+                }
+                Err(e) => {
+                    log::warn!("Cannot replace session {session_id}: {e}");
+                    let mut current = session_ref.write().unwrap();
+                    if current.as_ref() == Some(&session_id) {
+                        *current = None;
+                    }
+                }
+            }
+        }
+        log::debug!("Keep-alive of connection stopped");
+    });
+}
+
+/// Checks idle sessions of pool until pool is dropped
+pub(crate) fn spawn_for_pool<C: Credentials + Sync>(shared: Weak<Shared<C>>, options: KeepAliveOptions) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(options.interval).await;
+            let Some(shared) = shared.upgrade() else { break };
+            keep_alive_pool(SessionPool { shared }, &options).await;
+        }
+        log::debug!("Keep-alive of session pool stopped");
+    });
+}
+
+async fn keep_alive_pool<C: Credentials + Sync>(pool: SessionPool<C>, options: &KeepAliveOptions) {
+    let service = pool.shared.service();
+    let stale = {
+        let mut idle = pool.shared.idle.lock().unwrap();
+        let (stale, fresh) = std::mem::take(&mut *idle).into_iter().partition::<Vec<_>, _>(|s| s.checked.elapsed() >= options.idle_time);
+        *idle = fresh.into();
+        stale
+    };
+    let mut total = pool.idle() + pool.in_use() + stale.len();
+    let mut stale = stale.into_iter();
+    while let Some(session) = stale.next() {
+        if options.evict_after.is_some_and(|evict| session.since.elapsed() >= evict) && total > pool.shared.options.min_sessions {
+            log::debug!("Session {} is evicted from pool", session.id);
+            spawn_delete(service.clone(), session.id);
+            total -= 1;
+            continue;
+        }
+        match check(service.clone(), session.id.clone()).await {
+            Check::Ready => {
+                pool.shared.idle.lock().unwrap().push_front(IdleSession { checked: Instant::now(), ..session });
+            }
+            Check::Unknown => pool.shared.idle.lock().unwrap().push_front(session),
+            Check::Replace { busy } => {
+                log::debug!("Session {} is {}, replacing it", session.id, if busy { "busy" } else { "bad" });
+                if busy {
+                    spawn_delete(service.clone(), session.id);
+                }
+                //sessions could be created by `acquire` while stale ones were out of pool
+                if pool.idle() + pool.in_use() + stale.len() >= pool.shared.options.max_sessions.max(1) {
+                    log::debug!("Session pool is full, session is not replaced");
+                    total -= 1;
+                    continue;
+                }
+                match create_session(service.clone()).await {
+                    Ok(id) => pool.shared.idle.lock().unwrap().push_front(IdleSession::new(id)),
+                    Err(e) => {
+                        log::warn!("Cannot replace session of pool: {e}");
+                        total -= 1;
+                    }
+                }
+            }
+        }
+    }
+    if let Err(e) = pool.warm_up().await {
+        log::warn!("Cannot create sessions of pool: {e}");
+    }
+}
+
+#[test]
+fn keep_alive_statuses() {
+    use prost::Message;
+    use generated::ydb::operations::Operation;
+    use generated::ydb::table::KeepAliveResult;
+    let response = |status: StatusCode, session_status: SessionStatus| {
+        let result = KeepAliveResult { session_status: session_status.into() };
+        let result = generated::google::protobuf::Any { type_url: String::new(), value: result.encode_to_vec() };
+        let operation = Operation { ready: true, status: status.into(), result: Some(result), ..Default::default() };
+        KeepAliveResponse { operation: Some(operation) }
+    };
+    assert_eq!(check_response(response(StatusCode::Success, SessionStatus::Ready), "s"), Check::Ready);
+    assert_eq!(check_response(response(StatusCode::Success, SessionStatus::Busy), "s"), Check::Replace { busy: true });
+    assert_eq!(check_response(response(StatusCode::BadSession, SessionStatus::Unspecified), "s"), Check::Replace { busy: false });
+    assert_eq!(check_response(response(StatusCode::SessionBusy, SessionStatus::Unspecified), "s"), Check::Replace { busy: true });
+    assert_eq!(check_response(response(StatusCode::Overloaded, SessionStatus::Unspecified), "s"), Check::Unknown);
+    assert_eq!(check_response(KeepAliveResponse::default(), "s"), Check::Unknown);
+}
+
+#[tokio::test]
+async fn evict_keeps_min_sessions() {
+    use session_pool::SessionPoolOptions;
+    let channel = tonic::transport::Endpoint::from_static("http://localhost:1").connect_lazy();
+    let conn = client::YdbConnection::new(channel, "/local".try_into().unwrap(), String::new());
+    let pool = SessionPool::new(conn.service(), SessionPoolOptions { min_sessions: 1, ..Default::default() });
+    let old = Instant::now() - Duration::from_secs(10);
+    for id in ["s1", "s2", "s3"] {
+        pool.shared.idle.lock().unwrap().push_back(IdleSession { id: id.to_owned(), since: old, checked: old });
+    }
+    let options = KeepAliveOptions { idle_time: Duration::from_secs(1), evict_after: Some(Duration::from_secs(5)), ..Default::default() };
+    //last session is not evicted, and its check fails (server is unavailable), so it is kept as is
+    keep_alive_pool(pool.clone(), &options).await;
+    assert_eq!(pool.idle(), 1);
+    assert_eq!(pool.shared.idle.lock().unwrap()[0].checked, old);
+}
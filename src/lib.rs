@@ -1,4 +1,6 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
+// YdbError holds the whole failed Operation, so results with it are large by design
+#![allow(clippy::result_large_err)]
 #![doc = include_str!("../README.md")]
 //! ### Features
 //!  - [pool](pool/) - enables pool of connections (do not use with `sqlx`)
@@ -10,9 +12,26 @@ pub mod auth;
 pub mod error;
 mod payload;
 pub mod client;
+pub mod session_pool;
+pub mod keep_alive;
+pub mod driver;
+pub mod scheme;
+pub mod scripting;
+pub mod operation;
+pub mod index;
+pub mod coordination;
+pub mod rate_limiter;
+pub mod monitoring;
+pub mod topic;
+pub mod backup;
+pub mod dump;
+pub mod bulk;
+pub mod read_rows;
+pub mod read_table;
 
 
 pub use payload::YdbResponseWithResult;
+pub use payload::YdbResponseWithOperation;
 pub use client::YdbConnection;
 pub use client::YdbTransaction;
 pub use reimport::*;
@@ -0,0 +1,137 @@
+//! Monitoring service client to check health of database
+//!
+//! # Examples
+//! ```rust,no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//!     let mut conn = ydb_unofficial::YdbConnection::from_env();
+//!     let report = conn.health().await.unwrap();
+//!     println!("status: {:?}, latency: {:?}", report.status, report.latency);
+//!     for issue in &report.issues {
+//!         println!("{:?}: {} (nodes: {:?})", issue.status, issue.message, issue.nodes);
+//!     }
+//!     assert!(report.is_healthy());
+//! # }
+//! ```
+use std::time::{Duration, Instant};
+
+use super::*;
+use auth::Credentials;
+use client::{delegate, YdbConnection};
+use error::YdbError;
+use payload::YdbResponseWithResult;
+
+use generated::ydb::monitoring::v1::monitoring_service_client::MonitoringServiceClient;
+use generated::ydb::monitoring::*;
+
+pub use generated::ydb::monitoring::self_check::Result as SelfCheckStatus;
+pub use generated::ydb::monitoring::status_flag::Status as StatusFlag;
+
+/// [`MonitoringServiceClient`] wrapper, that checks status of each response.
+/// Use [`YdbConnection::monitoring`] to create it
+#[derive(Debug)]
+pub struct MonitoringClient<'a, C: Credentials> {
+    client: MonitoringServiceClient<&'a mut YdbConnection<C>>,
+}
+
+impl<'a, C: Credentials> MonitoringClient<'a, C> {
+    pub(crate) fn new(conn: &'a mut YdbConnection<C>) -> Self {
+        Self { client: MonitoringServiceClient::new(conn) }
+    }
+    delegate!{
+        fn self_check(SelfCheckRequest) -> SelfCheckResponse;
+        fn node_check(NodeCheckRequest) -> NodeCheckResponse;
+    }
+    /// Runs self check of database and measures its round-trip time
+    pub async fn health(&mut self) -> Result<HealthReport, YdbError> {
+        self.check_health(false).await
+    }
+    /// Like [`Self::health`], but also requests raw statuses of databases ([`HealthReport::databases`])
+    pub async fn verbose_health(&mut self) -> Result<HealthReport, YdbError> {
+        self.check_health(true).await
+    }
+    async fn check_health(&mut self, verbose: bool) -> Result<HealthReport, YdbError> {
+        let start = Instant::now();
+        let response = self.self_check(SelfCheckRequest { return_verbose_status: verbose, ..Default::default() }).await?;
+        let latency = start.elapsed();
+        let result = response.into_inner().result()?;
+        Ok(HealthReport::new(result, latency))
+    }
+}
+
+/// Result of database self check
+#[derive(Debug, Clone)]
+pub struct HealthReport {
+    /// Overall status of database
+    pub status: SelfCheckStatus,
+    pub issues: Vec<HealthIssue>,
+    /// Round-trip time of self check request
+    pub latency: Duration,
+    /// Raw statuses of databases (filled only by [`MonitoringClient::verbose_health`])
+    pub databases: Vec<DatabaseStatus>,
+}
+
+/// Problem found by self check
+#[derive(Debug, Clone)]
+pub struct HealthIssue {
+    pub id: String,
+    pub status: StatusFlag,
+    pub message: String,
+    /// Kind of issue, e.g. `STORAGE_GROUP`, `COMPUTE_NODE`
+    pub kind: String,
+    pub level: u32,
+    /// Affected nodes as `host:port`
+    pub nodes: Vec<String>,
+    /// Affected storage pools
+    pub storage_pools: Vec<String>,
+    /// Ids of issues, that caused this one
+    pub reason: Vec<String>,
+}
+
+fn node_address(node: &LocationNode) -> String {
+    format!("{}:{}", node.host, node.port)
+}
+
+impl From<IssueLog> for HealthIssue {
+    fn from(value: IssueLog) -> Self {
+        let status = value.status();
+        let IssueLog { id, message, location, reason, r#type, level, .. } = value;
+        let location = location.unwrap_or_default();
+        let storage = location.storage.unwrap_or_default();
+        let compute = location.compute.unwrap_or_default();
+        let nodes = storage.node.iter().chain(compute.node.iter()).map(node_address).collect();
+        let storage_pools = storage.pool.into_iter().map(|pool| pool.name).collect();
+        Self { id, status, message, kind: r#type, level, nodes, storage_pools, reason }
+    }
+}
+
+impl HealthReport {
+    fn new(result: SelfCheckResult, latency: Duration) -> Self {
+        let status = result.self_check_result();
+        let SelfCheckResult { issue_log, database_status, .. } = result;
+        let issues = issue_log.into_iter().map(HealthIssue::from).collect();
+        Self { status, issues, latency, databases: database_status }
+    }
+    /// Database works without problems
+    pub fn is_healthy(&self) -> bool {
+        self.status == SelfCheckStatus::Good
+    }
+    /// Database is able to serve requests, maybe with degraded performance
+    pub fn is_available(&self) -> bool {
+        matches!(self.status, SelfCheckStatus::Good | SelfCheckStatus::Degraded)
+    }
+    /// All nodes mentioned in issues (without duplicates)
+    pub fn affected_nodes(&self) -> Vec<&str> {
+        let mut nodes: Vec<_> = self.issues.iter().flat_map(|i| i.nodes.iter().map(String::as_str)).collect();
+        nodes.sort_unstable();
+        nodes.dedup();
+        nodes
+    }
+    /// All storage pools mentioned in issues (without duplicates)
+    pub fn affected_storage_pools(&self) -> Vec<&str> {
+        let mut pools: Vec<_> = self.issues.iter().flat_map(|i| i.storage_pools.iter().map(String::as_str)).collect();
+        pools.sort_unstable();
+        pools.dedup();
+        pools
+    }
+}
@@ -0,0 +1,203 @@
+//! Operation service client to poll, cancel and forget long-running operations.
+//!
+//! Many requests can be run in async mode (see [`async_params`]). In that case response contains
+//! [`Operation`] that is not ready yet. Clients report it as error (it has no status yet), so pass result of request
+//! to [`started`] to get the response. Use [`OperationClient::wait_ready`] to poll it until completion.
+//!
+//! # Examples
+//! ```rust,no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//!     use ydb_unofficial::generated::ydb::scripting::ExecuteYqlRequest;
+//!     use ydb_unofficial::operation::{async_params, started, WaitPolicy};
+//!     let mut conn = ydb_unofficial::YdbConnection::from_env();
+//!     let req = ExecuteYqlRequest {
+//!         script: "select 1;".to_owned(),
+//!         operation_params: Some(async_params()),
+//!         ..Default::default()
+//!     };
+//!     let response = started(conn.scripting().execute_yql(req).await).unwrap();
+//!     let result = conn.operation().wait_ready_result(response, WaitPolicy::default()).await.unwrap();
+//!     println!("result: {result:?}");
+//! # }
+//! ```
+use std::time::Duration;
+
+use futures::{Stream, StreamExt};
+
+use super::*;
+use auth::Credentials;
+use client::YdbConnection;
+use error::{YdbError, ErrWithOperation, check_status};
+use payload::{YdbResponseWithResult, YdbResponseWithOperation};
+
+use generated::ydb::status_ids::StatusCode;
+use generated::ydb::operation::v1::operation_service_client::OperationServiceClient;
+use generated::ydb::operations::*;
+use generated::ydb::operations::operation_params::OperationMode;
+
+/// Operation params to run request in async mode. Response of such request will contain not ready [`Operation`]
+pub fn async_params() -> OperationParams {
+    OperationParams { operation_mode: OperationMode::Async.into(), ..Default::default() }
+}
+
+/// Takes response of request, that was run in async mode: not ready operation is not an error
+pub fn started<T: YdbResponseWithOperation>(result: Result<tonic::Response<T>, YdbError>) -> Result<T, YdbError> {
+    match result {
+        Ok(response) => Ok(response.into_inner()),
+        Err(YdbError::Ydb(ErrWithOperation(operation))) if !operation.ready => Ok(T::from_operation(operation)),
+        Err(e) => Err(e),
+    }
+}
+
+/// Polling settings for [`OperationClient::wait_ready`]
+#[derive(Debug, Clone)]
+pub struct WaitPolicy {
+    /// Delay before first poll
+    pub interval: Duration,
+    /// Upper bound for delay between polls
+    pub max_interval: Duration,
+    /// Each next delay is multiplied by that value (until reach `max_interval`)
+    pub multiplier: f64,
+    /// Max time to wait. `None` means wait forever
+    pub timeout: Option<Duration>,
+}
+
+impl Default for WaitPolicy {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_millis(100),
+            max_interval: Duration::from_secs(5),
+            multiplier: 1.5,
+            timeout: None,
+        }
+    }
+}
+
+impl WaitPolicy {
+    fn next_interval(&self, interval: Duration) -> Duration {
+        interval.mul_f64(self.multiplier).min(self.max_interval)
+    }
+}
+
+/// [`OperationServiceClient`] wrapper, that checks status of each response.
+/// Use [`YdbConnection::operation`] to create it
+#[derive(Debug)]
+pub struct OperationClient<'a, C: Credentials> {
+    client: OperationServiceClient<&'a mut YdbConnection<C>>,
+}
+
+/// Returns ready operation as is, or error if operation failed
+fn check_ready(operation: Operation) -> Result<Operation, YdbError> {
+    match operation.status() {
+        StatusCode::Success => Ok(operation),
+        _ => Err(YdbError::Ydb(ErrWithOperation(operation))),
+    }
+}
+
+impl<'a, C: Credentials> OperationClient<'a, C> {
+    pub(crate) fn new(conn: &'a mut YdbConnection<C>) -> Self {
+        Self { client: OperationServiceClient::new(conn) }
+    }
+    /// Returns current state of operation. Not ready operation is not an error
+    pub async fn get(&mut self, id: &str) -> Result<Operation, YdbError> {
+        let response = self.client.get_operation(GetOperationRequest { id: id.to_owned() }).await?;
+        let operation = response.into_inner().operation.ok_or(YdbError::EmptyResponse)?;
+        if operation.ready {
+            check_ready(operation)
+        } else {
+            Ok(operation)
+        }
+    }
+    /// Requests cancellation of operation
+    pub async fn cancel(&mut self, id: &str) -> Result<(), YdbError> {
+        let response = self.client.cancel_operation(CancelOperationRequest { id: id.to_owned() }).await?.into_inner();
+        check_status(response.status, response.issues)
+    }
+    /// Forgets operation: server stops to store it, so it cannot be requested anymore
+    pub async fn forget(&mut self, id: &str) -> Result<(), YdbError> {
+        let response = self.client.forget_operation(ForgetOperationRequest { id: id.to_owned() }).await?.into_inner();
+        check_status(response.status, response.issues)
+    }
+    /// Lists operations of some kind (e.g. `export`, `import`, `buildindex`).
+    /// Returns operations and token of next page (empty if there are no more pages)
+    pub async fn list(&mut self, kind: &str, page_size: u64, page_token: String) -> Result<(Vec<Operation>, String), YdbError> {
+        let req = ListOperationsRequest { kind: kind.to_owned(), page_size, page_token };
+        let response = self.client.list_operations(req).await?.into_inner();
+        check_status(response.status, response.issues)?;
+        Ok((response.operations, response.next_page_token))
+    }
+    /// Polls operation until it becomes ready. Returns ready operation or error if operation failed.
+    /// If `timeout` of policy exceeded, returns [`YdbError::OperationTimeout`] with id of operation
+    pub async fn wait_ready(&mut self, mut operation: Operation, policy: WaitPolicy) -> Result<Operation, YdbError> {
+        let start = std::time::Instant::now();
+        let mut interval = policy.interval;
+        while !operation.ready {
+            if operation.id.is_empty() {
+                return Err(YdbError::EmptyResponse);
+            }
+            if let Some(timeout) = policy.timeout {
+                let elapsed = start.elapsed();
+                if elapsed >= timeout {
+                    return Err(YdbError::OperationTimeout(operation.id));
+                }
+                interval = interval.min(timeout - elapsed);
+            }
+            tokio::time::sleep(interval).await;
+            interval = policy.next_interval(interval);
+            log::debug!("Polling operation {}", operation.id);
+            operation = self.get(&operation.id).await?;
+        }
+        check_ready(operation)
+    }
+    /// Returns stream of operation states to watch progress: the given operation, then polled states until it becomes ready.
+    /// The last item is ready operation or error
+    pub fn watch(self, operation: Operation, policy: WaitPolicy) -> impl Stream<Item = Result<Operation, YdbError>> + 'a {
+        let start = std::time::Instant::now();
+        let state = if operation.ready { None } else { Some((self, operation.clone(), policy.interval)) };
+        let first = if operation.ready { check_ready(operation) } else { Ok(operation) };
+        let polled = futures::stream::unfold(state, move |state| {
+            let policy = policy.clone();
+            async move {
+                let (mut client, operation, interval) = state?;
+                if matches!(policy.timeout, Some(timeout) if start.elapsed() >= timeout) {
+                    return Some((Err(YdbError::OperationTimeout(operation.id)), None));
+                }
+                tokio::time::sleep(interval).await;
+                match client.get(&operation.id).await {
+                    Ok(operation) if operation.ready => Some((Ok(operation), None)),
+                    Ok(operation) => {
+                        let interval = policy.next_interval(interval);
+                        Some((Ok(operation.clone()), Some((client, operation, interval))))
+                    }
+                    Err(e) => Some((Err(e), None)),
+                }
+            }
+        });
+        futures::stream::once(futures::future::ready(first)).chain(polled)
+    }
+    /// Waits operation of response (see [`Self::wait_ready`]), then decodes its result
+    pub async fn wait_ready_result<T>(&mut self, response: T, policy: WaitPolicy) -> Result<T::Result, YdbError>
+    where T: YdbResponseWithResult + YdbResponseWithOperation {
+        let operation = response.operation().cloned().ok_or(YdbError::EmptyResponse)?;
+        let operation = self.wait_ready(operation, policy).await?;
+        Ok(T::from_operation(operation).result()?)
+    }
+}
+
+#[test]
+fn wait_policy_backoff() {
+    let policy = WaitPolicy { interval: Duration::from_secs(1), max_interval: Duration::from_secs(3), multiplier: 2.0, timeout: None };
+    let second = policy.next_interval(policy.interval);
+    assert_eq!(second, Duration::from_secs(2));
+    assert_eq!(policy.next_interval(second), Duration::from_secs(3));
+}
+
+#[test]
+fn not_ready_operation_is_started() {
+    use generated::ydb::scripting::ExecuteYqlResponse;
+    let failed = |ready| Err(YdbError::Ydb(ErrWithOperation(Operation { id: "op".to_owned(), ready, ..Default::default() })));
+    let response = started::<ExecuteYqlResponse>(failed(false)).unwrap();
+    assert_eq!(response.operation().map(|o| o.id.as_str()), Some("op"));
+    assert!(started::<ExecuteYqlResponse>(failed(true)).is_err());
+}
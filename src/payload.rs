@@ -1,7 +1,16 @@
 
-use crate::generated::ydb::{table, discovery};
+use crate::generated::ydb::{table, discovery, scheme, scripting, coordination, rate_limiter, monitoring, topic};
+use crate::generated::ydb::export::ExportToS3Response;
+use crate::generated::ydb::import::ImportFromS3Response;
+use crate::generated::ydb::operations::Operation;
 use table::*;
 use discovery::*;
+use scheme::*;
+use scripting::*;
+use coordination::*;
+use rate_limiter::*;
+use monitoring::*;
+use topic::*;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -19,8 +28,29 @@ pub trait YdbResponseWithResult {
 }
 
 
+/// The trait to access [`Operation`] of response. Used to wait long-running operations, see [`crate::operation`]
+pub trait YdbResponseWithOperation: Sized {
+    fn operation(&self) -> Option<&Operation>;
+    /// Creates response with operation, e.g. with ready operation after polling
+    fn from_operation(operation: Operation) -> Self;
+}
+
+macro_rules! operational {
+    ($($x:ty,)+) => {$(
+        impl YdbResponseWithOperation for $x {
+            fn operation(&self) -> Option<&Operation> {
+                self.operation.as_ref()
+            }
+            fn from_operation(operation: Operation) -> Self {
+                Self { operation: Some(operation) }
+            }
+        }
+    )+}
+}
+
 macro_rules! payloaded {
     ($($x:ty : $p:ty,)+) => {$(
+        operational!($x,);
         impl YdbResponseWithResult for $x {
             type Result = $p;
             fn result(&self) -> Result<Self::Result, ExtractResultError> {
@@ -36,7 +66,10 @@ macro_rules! payloaded {
     )+}
 }
 
+#[allow(unused_imports)]
 pub(crate) use payloaded;
+#[allow(unused_imports)]
+pub(crate) use operational;
 
 payloaded!(
     WhoAmIResponse: WhoAmIResult, 
@@ -52,4 +85,26 @@ payloaded!(
     DescribeTableOptionsResponse: DescribeTableOptionsResult,
     DescribeTableResponse: DescribeTableResult,
     KeepAliveResponse: KeepAliveResult,
+
+    ListDirectoryResponse: ListDirectoryResult,
+    DescribePathResponse: DescribePathResult,
+
+    ExecuteYqlResponse: ExecuteYqlResult,
+    ExplainYqlResponse: ExplainYqlResult,
+
+    DescribeNodeResponse: DescribeNodeResult,
+
+    ListResourcesResponse: ListResourcesResult,
+    DescribeResourceResponse: DescribeResourceResult,
+
+    SelfCheckResponse: SelfCheckResult,
+
+    DescribeTopicResponse: DescribeTopicResult,
+    DescribeConsumerResponse: DescribeConsumerResult,
+);
+
+operational!(
+    AlterTableResponse,
+    ExportToS3Response,
+    ImportFromS3Response,
 );
\ No newline at end of file
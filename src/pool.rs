@@ -1,5 +1,7 @@
 //! Implementation of pool of [`YdbConnection`].
-//! Uses method `list_endpoints` of `DiscoveryServiceClient` to create pool on multiple endpoints
+//! Connections are created by [`Driver`], that discovers endpoints of database and balances connections between them.
+//! Pooled connections of one endpoint share its grpc channels (see [`YdbPoolBuilder::channels_per_endpoint`]),
+//! connections of endpoint, that disappeared from discovery, are dropped on recycle.
 //! # Examples
 //! ```rust
 //! # #[tokio::main]
@@ -17,34 +19,23 @@
 //! # }
 //! ```
 use super::*;
-use std::{vec, time::Duration};
+use std::time::Duration;
 
-use deadpool::managed::{Manager, Pool, PoolBuilder, PoolConfig, Hook};
+use deadpool::managed::{Manager, Pool, PoolBuilder, PoolConfig, Hook, RecycleError};
 
 use tonic::transport::{Endpoint, Uri};
 use tower::ServiceExt;
 
-use payload::YdbResponseWithResult;
-use generated::ydb::discovery::{EndpointInfo, ListEndpointsRequest};
+use generated::ydb::discovery::EndpointInfo;
 use auth::Credentials;
 use crate::client::YdbEndpoint;
+use crate::driver::{Driver, DriverOptions, pick};
+use crate::keep_alive::KeepAliveOptions;
+use crate::error::YdbError;
 
 
-type YdbEndpoints = std::sync::RwLock<Vec<YdbEndpoint>>;
 pub type YdbPool<C> = Pool<ConnectionManager<C>>;
 
-impl From<EndpointInfo> for YdbEndpoint {
-    fn from(value: EndpointInfo) -> Self {
-        Self {
-            ssl: value.ssl,
-            host: value.address,
-            port: value.port as u16,
-            load_factor: value.load_factor,
-        }
-    }
-}
-
-
 fn make_endpoint(info: &YdbEndpoint) -> Endpoint {
     let uri: tonic::transport::Uri = format!("{}://{}:{}", info.scheme(), info.host, info.port).try_into().unwrap();
     let mut e = Endpoint::from(uri).tcp_keepalive(Some(std::time::Duration::from_secs(15)));
@@ -64,31 +55,30 @@ impl GetScheme for EndpointInfo {
     }
 }
 
-pub struct ConnectionManager<C> {
-    creds: C,
-    db_name: AsciiValue,
-    endpoints: YdbEndpoints,
+/// Settings of connections of pool, see [`ConnectionManager::new`]
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionOptions {
+    /// Background keep-alive of sessions of pooled connections, see [`crate::keep_alive`]
+    pub keep_alive: Option<KeepAliveOptions>,
+}
+
+pub struct ConnectionManager<C: Credentials> {
+    driver: Driver<C>,
+    options: ConnectionOptions,
 }
 
-impl<C: Credentials> ConnectionManager<C> {
+impl<C: Credentials + Sync> ConnectionManager<C> {
+    /// Creates manager of connections, that are created by `driver`
+    pub fn new(driver: Driver<C>, options: ConnectionOptions) -> Self {
+        Self { driver, options }
+    }
+    /// Driver, that creates connections of pool
+    pub fn driver(&self) -> &Driver<C> {
+        &self.driver
+    }
     pub fn next_endpoint(&self) -> Endpoint {
-        let endpoints = self.endpoints.read().unwrap();
-        if endpoints.len() == 1 {
-            return endpoints.first().unwrap().make_endpoint();
-        } else if endpoints.is_empty() {
-            panic!("List of endpoints is empty");
-        }
-        let mut rng = rand::thread_rng();
-        use rand::Rng;
-        let e1 = rng.gen::<usize>() % endpoints.len();
-        let mut e2 = e1;
-        while e2 == e1 { //TODO: кажется, это что-то неоптимальное
-            e2 = rng.gen::<usize>() % endpoints.len();
-        }
-        let e1 = &endpoints[e1];
-        let e2 = &endpoints[e2];
-        let endpoint = if e1.load_factor < e2.load_factor {e1} else { e2 };
-        make_endpoint(&endpoint)
+        let endpoints = self.driver.endpoints();
+        make_endpoint(&endpoints[pick(&endpoints, |e| e.load_factor)])
     }
 }
 
@@ -99,29 +89,49 @@ impl <C: Credentials + Sync> Manager for ConnectionManager<C> {
     type Error = tonic::transport::Error;
 
     async fn create(&self) ->  Result<Self::Type, Self::Error> {
-        let endpoint = self.next_endpoint();
-        let channel = endpoint.connect().await?;
-        let db_name = self.db_name.clone();
-        let creds = self.creds.clone();
-        Ok(YdbConnection::new(channel, db_name, creds))
+        let conn = self.driver.connection();
+        Ok(match &self.options.keep_alive {
+            Some(options) => conn.with_keep_alive(options.clone()),
+            None => conn,
+        })
     }
 
     async fn recycle(&self, obj: &mut Self::Type) ->  deadpool::managed::RecycleResult<Self::Error> {
+        if let Some(endpoint) = obj.endpoint() {
+            if !self.driver.endpoints().iter().any(|e| e.same_address(endpoint)) {
+                let address = format!("{}:{}", endpoint.host, endpoint.port);
+                return Err(RecycleError::Message(format!("Endpoint {address} is not in discovery anymore")));
+            }
+        }
         obj.ready().await?;
         Ok(())
     }
 }
 
+type Setting<C> = Box<dyn FnOnce(PoolBuilder<ConnectionManager<C>>) -> PoolBuilder<ConnectionManager<C>> + Send>;
+
 /// Builder for pool of [`YdbConnection`]
 pub struct YdbPoolBuilder<C: Credentials + Send + Sync> {
-    inner: PoolBuilder<ConnectionManager<C>>,
-    update_interval: Duration,
+    creds: C,
+    db_name: AsciiValue,
+    endpoint: YdbEndpoint,
+    driver: DriverOptions,
+    options: ConnectionOptions,
+    /// Settings of [`PoolBuilder`], that are applied on build
+    settings: Vec<Setting<C>>,
 }
 
 macro_rules! delegate {
     ($( $fun:ident($param:ty), )+) => { $(
         pub fn $fun(mut self, v: $param) -> Self {
-            self.inner = self.inner.$fun(v);
+            self.settings.push(Box::new(move |builder| builder.$fun(v)));
+            self
+        }
+    )+ };
+    (hooks: $( $fun:ident, )+) => { $(
+        pub fn $fun(mut self, hook: impl Into<Hook<ConnectionManager<C>>>) -> Self {
+            let hook = hook.into();
+            self.settings.push(Box::new(move |builder| builder.$fun(hook)));
             self
         }
     )+ };
@@ -129,57 +139,52 @@ macro_rules! delegate {
 /// Wrapper on [`PoolBuilder`] for YdbConnection.
 impl<C: Credentials + Send + Sync> YdbPoolBuilder<C> {
     pub fn new(creds: C, db_name: AsciiValue, endpoint: YdbEndpoint) -> Self {
-        let endpoints =  std::sync::RwLock::new(vec![endpoint]);
-        let inner = Pool::builder(ConnectionManager {creds, db_name, endpoints});
-        let update_interval = Duration::from_secs(77);
-        Self {inner, update_interval}
+        let driver = DriverOptions { discovery_interval: Duration::from_secs(77), ..Default::default() };
+        Self {creds, db_name, endpoint, driver, options: Default::default(), settings: Vec::new()}
     }
     /// Set period to update endpoints for pool. Default is 77 seconds.
     pub fn update_interval(mut self, interval: Duration) -> Self {
-        self.update_interval = interval;
+        self.driver.discovery_interval = interval;
+        self
+    }
+    /// Enables background keep-alive of sessions of pooled connections, see [`crate::keep_alive`]
+    pub fn keep_alive(mut self, options: KeepAliveOptions) -> Self {
+        self.options.keep_alive = Some(options);
+        self
+    }
+    /// Set count of grpc channels (HTTP/2 connections) per endpoint. Pooled connections share these channels. Default is 1.
+    pub fn channels_per_endpoint(mut self, count: usize) -> Self {
+        self.driver.channels_per_endpoint = count;
         self
     }
     delegate!{ 
         config(PoolConfig),
         create_timeout(Option<Duration>),
         max_size(usize),
-        post_create(impl Into<Hook<ConnectionManager<C>>>),
-        post_recycle(impl Into<Hook<ConnectionManager<C>>>),
-        pre_recycle(impl Into<Hook<ConnectionManager<C>>>),
         recycle_timeout(Option<Duration>),
         runtime(deadpool::Runtime),
         timeouts(deadpool::managed::Timeouts),
         wait_timeout(Option<Duration>),
     }
+    delegate!{ hooks:
+        post_create,
+        post_recycle,
+        pre_recycle,
+    }
+    /// Builds pool. Must be called inside tokio runtime: driver of pool discovers endpoints in background
     pub fn build(self) -> Result<Pool<ConnectionManager<C>>, deadpool::managed::BuildError<tonic::transport::Error>> {
-        let pool = self.inner.build()?;
-        let result = pool.clone();
-        let db_name = pool.manager().db_name.to_str().unwrap().to_owned();
-        tokio::spawn(async move {
-            loop {
-                if pool.is_closed() {
-                    log::debug!("Connection pool closed");
-                    break;
-                }
-                if let Err(e) = update_endpoints(&pool, db_name.clone()).await {
-                    log::error!("Error on update endpoints for pool: {e:?}");
-                }
-                tokio::time::sleep(self.update_interval).await;
-            }
-        });
-        Ok(result)
+        let driver = Driver::new_lazy(self.creds, self.db_name, self.endpoint, self.driver);
+        let manager = ConnectionManager::new(driver, self.options);
+        self.settings.into_iter().fold(Pool::builder(manager), |builder, setting| setting(builder)).build()
     }
 }
 
-async fn update_endpoints<C: Credentials + Send + Sync>(pool: &Pool<ConnectionManager<C>>, database: String) -> Result<(), Box<dyn std::error::Error>> {
-    let mut service = pool.get().await?;
-    let mut discovery = service.discovery();
-    let response = discovery.list_endpoints(ListEndpointsRequest{database, ..Default::default()}).await?; 
-    let endpoints: Vec<_> = response.into_inner().result()?.endpoints.into_iter().map(From::from).collect();
-    log::debug!("Pool endpoints updated ({} endpoints)", endpoints.len());
-    *pool.manager().endpoints.write().unwrap() = endpoints;
-    Ok(())
+/// Checks health of database with connection from pool. See [`crate::monitoring`]
+pub async fn health<C: Credentials + Send + Sync>(pool: &YdbPool<C>) -> Result<crate::monitoring::HealthReport, YdbError> {
+    let mut conn = pool.get().await?;
+    conn.health().await
 }
+
 pub fn to_endpoint_info(value: Uri) -> Result<EndpointInfo, String> {
     let mut e = EndpointInfo::default();
     e.ssl = match value.scheme_str() {
@@ -190,4 +195,16 @@ pub fn to_endpoint_info(value: Uri) -> Result<EndpointInfo, String> {
     e.address = value.host().ok_or("no host")?.to_owned();
     e.port = value.port_u16().ok_or("no port")? as u32;
     Ok(e)
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn builder_applies_settings() {
+    let endpoint = YdbEndpoint { ssl: false, host: "localhost".to_owned(), port: 1, load_factor: 0.0 };
+    let pool = YdbPoolBuilder::new(String::new(), "/local".try_into().unwrap(), endpoint)
+        .max_size(5)
+        .channels_per_endpoint(2)
+        .build().unwrap();
+    assert_eq!(pool.status().max_size, 5);
+    let (a, b, c) = futures::join!(pool.get(), pool.get(), pool.get());
+    assert!([a, b, c].iter().all(|conn| conn.as_ref().unwrap().endpoint().is_some_and(|e| e.port == 1)));
+}
@@ -0,0 +1,237 @@
+//! Rate limiter service client. Resources of rate limiter are stored in coordination nodes (see [`crate::coordination`]).
+//!
+//! For hot paths use [`QuotaCache`]: it acquires units in batches and spends them locally.
+//!
+//! # Examples
+//! ```rust,no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//!     use ydb_unofficial::generated::ydb::rate_limiter::HierarchicalDrrSettings;
+//!     use ydb_unofficial::rate_limiter::QuotaCacheOptions;
+//!     let mut conn = ydb_unofficial::YdbConnection::from_env();
+//!     let mut limiter = conn.rate_limiter();
+//!     let settings = HierarchicalDrrSettings { max_units_per_second: 100.0, ..Default::default() };
+//!     limiter.create("limits", "tenant-1", settings).await.unwrap();
+//!     let quota = limiter.quota_cache("limits", "tenant-1", QuotaCacheOptions::default());
+//!     quota.acquire(1).await.unwrap();
+//! # }
+//! ```
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use super::*;
+use auth::Credentials;
+use client::{delegate, YdbConnection, YdbService};
+use error::YdbError;
+use payload::YdbResponseWithResult;
+
+use generated::google::protobuf;
+use generated::ydb::operations::OperationParams;
+use generated::ydb::rate_limiter::v1::rate_limiter_service_client::RateLimiterServiceClient;
+use generated::ydb::rate_limiter::*;
+use generated::ydb::rate_limiter::acquire_resource_request::Units;
+
+/// [`RateLimiterServiceClient`] wrapper, that checks status of each response.
+/// Use [`YdbConnection::rate_limiter`] to create it
+#[derive(Debug)]
+pub struct RateLimiterClient<'a, C: Credentials> {
+    service: YdbService<C>,
+    client: RateLimiterServiceClient<&'a mut YdbConnection<C>>,
+}
+
+fn resource(path: &str, settings: HierarchicalDrrSettings) -> Option<Resource> {
+    Some(Resource { resource_path: path.to_owned(), r#type: Some(resource::Type::HierarchicalDrr(settings)) })
+}
+
+fn acquire_request(node: &str, path: &str, units: Units, timeout: Option<Duration>) -> AcquireResourceRequest {
+    let operation_params = timeout.map(|t| OperationParams {
+        operation_timeout: Some(protobuf::Duration { seconds: t.as_secs() as i64, nanos: t.subsec_nanos() as i32 }),
+        ..Default::default()
+    });
+    AcquireResourceRequest {
+        operation_params,
+        coordination_node_path: node.to_owned(),
+        resource_path: path.to_owned(),
+        units: Some(units),
+    }
+}
+
+impl<'a, C: Credentials> RateLimiterClient<'a, C> {
+    pub(crate) fn new(conn: &'a mut YdbConnection<C>) -> Self {
+        let service = conn.service();
+        Self { service, client: RateLimiterServiceClient::new(conn) }
+    }
+    delegate!{
+        fn create_resource(CreateResourceRequest) -> CreateResourceResponse;
+        fn alter_resource(AlterResourceRequest) -> AlterResourceResponse;
+        fn drop_resource(DropResourceRequest) -> DropResourceResponse;
+        fn list_resources(ListResourcesRequest) -> ListResourcesResponse;
+        fn describe_resource(DescribeResourceRequest) -> DescribeResourceResponse;
+        fn acquire_resource(AcquireResourceRequest) -> AcquireResourceResponse;
+    }
+    /// Creates resource in coordination node. Nested resources are separated by `/` (e.g. `api/tenant-1`)
+    pub async fn create(&mut self, node: &str, path: &str, settings: HierarchicalDrrSettings) -> Result<(), YdbError> {
+        let req = CreateResourceRequest { coordination_node_path: node.to_owned(), resource: resource(path, settings), ..Default::default() };
+        self.create_resource(req).await?;
+        Ok(())
+    }
+    pub async fn alter(&mut self, node: &str, path: &str, settings: HierarchicalDrrSettings) -> Result<(), YdbError> {
+        let req = AlterResourceRequest { coordination_node_path: node.to_owned(), resource: resource(path, settings), ..Default::default() };
+        self.alter_resource(req).await?;
+        Ok(())
+    }
+    pub async fn drop(&mut self, node: &str, path: &str) -> Result<(), YdbError> {
+        let req = DropResourceRequest { coordination_node_path: node.to_owned(), resource_path: path.to_owned(), ..Default::default() };
+        self.drop_resource(req).await?;
+        Ok(())
+    }
+    /// Returns paths of resources, nested to `path`
+    pub async fn list(&mut self, node: &str, path: &str, recursive: bool) -> Result<Vec<String>, YdbError> {
+        let req = ListResourcesRequest { coordination_node_path: node.to_owned(), resource_path: path.to_owned(), recursive, ..Default::default() };
+        let response = self.list_resources(req).await?;
+        Ok(response.into_inner().result()?.resource_paths)
+    }
+    pub async fn describe(&mut self, node: &str, path: &str) -> Result<Resource, YdbError> {
+        let req = DescribeResourceRequest { coordination_node_path: node.to_owned(), resource_path: path.to_owned(), ..Default::default() };
+        let response = self.describe_resource(req).await?;
+        response.into_inner().result()?.resource.ok_or(YdbError::EmptyResponse)
+    }
+    /// Waits until `units` are available and spends them. Fails with `Timeout` status if units are not acquired in `timeout`
+    pub async fn acquire(&mut self, node: &str, path: &str, units: u64, timeout: Option<Duration>) -> Result<(), YdbError> {
+        self.acquire_resource(acquire_request(node, path, Units::Required(units), timeout)).await?;
+        Ok(())
+    }
+    /// Reports units, that was already spent without acquiring
+    pub async fn report_used(&mut self, node: &str, path: &str, units: u64) -> Result<(), YdbError> {
+        self.acquire_resource(acquire_request(node, path, Units::Used(units), None)).await?;
+        Ok(())
+    }
+    /// Creates [`QuotaCache`] for resource. Cache doesn't borrow connection
+    pub fn quota_cache(&self, node: &str, path: &str, options: QuotaCacheOptions) -> QuotaCache<C> {
+        QuotaCache {
+            client: tokio::sync::Mutex::new(RateLimiterServiceClient::new(self.service.clone())),
+            node: node.to_owned(),
+            path: path.to_owned(),
+            options,
+            bucket: std::sync::Mutex::new(Bucket::default()),
+        }
+    }
+}
+
+/// Settings of [`QuotaCache`]
+#[derive(Debug, Clone)]
+pub struct QuotaCacheOptions {
+    /// Units to acquire from server at once
+    pub batch_size: u64,
+    /// Acquired units that not spent during this time are dropped, so cache cannot exceed rate of resource
+    pub ttl: Duration,
+    /// Max time to wait units from server
+    pub timeout: Option<Duration>,
+}
+
+impl Default for QuotaCacheOptions {
+    fn default() -> Self {
+        Self { batch_size: 10, ttl: Duration::from_secs(1), timeout: None }
+    }
+}
+
+/// Cached units by batches: each batch expires after ttl since its acquisition
+#[derive(Debug, Default)]
+struct Bucket {
+    /// Oldest batches are at the front
+    batches: VecDeque<(Instant, u64)>,
+}
+
+impl Bucket {
+    fn purge(&mut self, ttl: Duration, now: Instant) {
+        while matches!(self.batches.front(), Some((at, _)) if now.duration_since(*at) > ttl) {
+            self.batches.pop_front();
+        }
+    }
+    fn take(&mut self, units: u64, ttl: Duration, now: Instant) -> bool {
+        if self.available(ttl, now) < units {
+            return false;
+        }
+        let mut rest = units;
+        while rest > 0 {
+            let (_, batch) = self.batches.front_mut().unwrap();
+            let spent = rest.min(*batch);
+            *batch -= spent;
+            rest -= spent;
+            if *batch == 0 {
+                self.batches.pop_front();
+            }
+        }
+        true
+    }
+    fn put(&mut self, units: u64, now: Instant) {
+        self.batches.push_back((now, units));
+    }
+    fn available(&mut self, ttl: Duration, now: Instant) -> u64 {
+        self.purge(ttl, now);
+        self.batches.iter().map(|(_, units)| units).sum()
+    }
+}
+
+/// Local cache of rate limiter quota. Acquires units from server in batches, so most of [`QuotaCache::acquire`] calls make no RPC
+#[derive(Debug)]
+pub struct QuotaCache<C: Credentials> {
+    client: tokio::sync::Mutex<RateLimiterServiceClient<YdbService<C>>>,
+    node: String,
+    path: String,
+    options: QuotaCacheOptions,
+    bucket: std::sync::Mutex<Bucket>,
+}
+
+impl<C: Credentials> QuotaCache<C> {
+    fn try_take(&self, units: u64) -> bool {
+        self.bucket.lock().unwrap().take(units, self.options.ttl, Instant::now())
+    }
+    /// Spends `units` of quota. If cached units are not enough, acquires new batch from server
+    pub async fn acquire(&self, units: u64) -> Result<(), YdbError> {
+        if self.try_take(units) {
+            return Ok(());
+        }
+        //only one request to server at once, others wait for it and spend its units
+        let mut client = self.client.lock().await;
+        if self.try_take(units) {
+            return Ok(());
+        }
+        let required = units.max(self.options.batch_size);
+        let req = acquire_request(&self.node, &self.path, Units::Required(required), self.options.timeout);
+        let response = client.acquire_resource(req).await?;
+        let operation = response.into_inner().operation.ok_or(YdbError::EmptyResponse)?;
+        error::check_status(operation.status, operation.issues)?;
+        let mut bucket = self.bucket.lock().unwrap();
+        bucket.put(required, Instant::now());
+        bucket.take(units, self.options.ttl, Instant::now());
+        Ok(())
+    }
+    /// Returns count of cached units, that are not expired
+    pub fn available(&self) -> u64 {
+        self.bucket.lock().unwrap().available(self.options.ttl, Instant::now())
+    }
+}
+
+#[test]
+fn quota_bucket() {
+    let ttl = Duration::from_secs(1);
+    let now = Instant::now();
+    let mut bucket = Bucket::default();
+    assert!(!bucket.take(1, ttl, now));
+    bucket.put(10, now);
+    assert!(bucket.take(4, ttl, now));
+    assert!(bucket.take(6, ttl, now));
+    assert!(!bucket.take(1, ttl, now));
+    bucket.put(10, now);
+    assert!(!bucket.take(1, ttl, now + Duration::from_secs(2)));
+    assert_eq!(bucket.available(ttl, now), 0);
+    //units of older batch expire even if newer batch is acquired
+    bucket.put(5, now);
+    bucket.put(5, now + Duration::from_millis(800));
+    assert!(bucket.take(2, ttl, now));
+    assert!(!bucket.take(6, ttl, now + Duration::from_millis(1500)));
+    assert_eq!(bucket.available(ttl, now + Duration::from_millis(1500)), 5);
+    //expired batch is not counted without take
+    assert_eq!(bucket.available(ttl, now + Duration::from_secs(2)), 0);
+}
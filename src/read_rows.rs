@@ -0,0 +1,89 @@
+//! `ReadRows` request of table service: reading of rows by primary keys without compilation of YQL query.
+//! `ydb-grpc-bindings` has no messages of this request yet, so they are declared here
+//!
+//! # Examples
+//! ```rust,no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//!     use ydb_unofficial::bulk::Row;
+//!     let mut conn = ydb_unofficial::YdbConnection::from_env();
+//!     let keys = [1u64, 2, 3].map(|id| Row::new().set("id", id));
+//!     let result_set = conn.read_rows("users", keys, &["id", "name"]).await.unwrap();
+//!     println!("found {} rows", result_set.rows.len());
+//! # }
+//! ```
+use super::*;
+use auth::Credentials;
+use client::YdbConnection;
+use error::{YdbError, check_status};
+use bulk::{Row, rows_list};
+
+use generated::ydb::{ResultSet, TypedValue};
+use generated::ydb::issue::IssueMessage;
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReadRowsRequest {
+    /// Session is not required for this request
+    #[prost(string, tag = "1")]
+    pub session_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub path: ::prost::alloc::string::String,
+    /// Keys to read: list of structs with key columns
+    #[prost(message, optional, tag = "3")]
+    pub keys: ::core::option::Option<TypedValue>,
+    /// Columns to read (all columns if empty)
+    #[prost(string, repeated, tag = "4")]
+    pub columns: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReadRowsResponse {
+    #[prost(enumeration = "generated::ydb::status_ids::StatusCode", tag = "1")]
+    pub status: i32,
+    #[prost(message, repeated, tag = "2")]
+    pub issues: ::prost::alloc::vec::Vec<IssueMessage>,
+    #[prost(message, optional, tag = "3")]
+    pub result_set: ::core::option::Option<ResultSet>,
+}
+
+/// Client of `ReadRows` method, that checks status of response.
+/// Use [`YdbConnection::read_rows_client`] to create it
+#[derive(Debug)]
+pub struct ReadRowsClient<'a, C: Credentials> {
+    client: tonic::client::Grpc<&'a mut YdbConnection<C>>,
+}
+
+impl<'a, C: Credentials> ReadRowsClient<'a, C> {
+    pub(crate) fn new(conn: &'a mut YdbConnection<C>) -> Self {
+        Self { client: tonic::client::Grpc::new(conn) }
+    }
+    pub async fn read_rows(&mut self, req: ReadRowsRequest) -> Result<ResultSet, YdbError> {
+        self.client.ready().await.map_err(|e| tonic::Status::unknown(format!("Service was not ready: {e}")))?;
+        let codec = tonic::codec::ProstCodec::default();
+        let path = tonic::codegen::http::uri::PathAndQuery::from_static("/Ydb.Table.V1.TableService/ReadRows");
+        let response: tonic::Response<ReadRowsResponse> = self.client.unary(tonic::Request::new(req), path, codec).await?;
+        let ReadRowsResponse { status, issues, result_set } = response.into_inner();
+        check_status(status, issues)?;
+        Ok(result_set.unwrap_or_default())
+    }
+    /// Reads rows of table by primary keys. Returns rows in unspecified order, absent keys are skipped
+    pub async fn read(&mut self, table: &str, keys: impl IntoIterator<Item = Row>, columns: &[&str]) -> Result<ResultSet, YdbError> {
+        let req = ReadRowsRequest {
+            path: table.to_owned(),
+            keys: Some(rows_list(keys)?),
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            ..Default::default()
+        };
+        self.read_rows(req).await
+    }
+}
+
+#[test]
+fn read_rows_wire_format() {
+    use prost::Message;
+    let req = ReadRowsRequest { path: "t".to_owned(), columns: vec!["id".to_owned()], ..Default::default() };
+    assert_eq!(req.encode_to_vec(), b"\x12\x01t\x22\x02id");
+    let response = ReadRowsResponse::decode(&b"\x08\x80\xb5\x18\x1a\x00"[..]).unwrap();
+    assert_eq!(response.status(), generated::ydb::status_ids::StatusCode::Success);
+    assert_eq!(response.result_set, Some(ResultSet::default()));
+}
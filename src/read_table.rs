@@ -0,0 +1,144 @@
+//! Reading of whole table (or range of primary keys) without YQL query, see [`TableClientWithSession::read_table`]
+//!
+//! # Examples
+//! ```rust,no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//!     use std::ops::Bound;
+//!     use futures::StreamExt;
+//!     use ydb_unofficial::bulk::Row;
+//!     use ydb_unofficial::read_table::ReadTableOptions;
+//!     let mut conn = ydb_unofficial::YdbConnection::from_env();
+//!     let options = ReadTableOptions {
+//!         from: Bound::Included(Row::new().set("id", Some(100u64))),
+//!         to: Bound::Excluded(Row::new().set("id", Some(200u64))),
+//!         columns: vec!["id".to_owned(), "name".to_owned()],
+//!         ordered: true,
+//!         ..Default::default()
+//!     };
+//!     let mut parts = conn.table().await.unwrap().read_table("users", options).await.unwrap();
+//!     while let Some(result_set) = parts.next().await {
+//!         println!("rows: {}", result_set.unwrap().rows.len());
+//!     }
+//! # }
+//! ```
+use std::ops::Bound;
+
+use futures::{Stream, StreamExt};
+
+use super::*;
+use auth::Credentials;
+use bulk::Row;
+use client::{TableClientWithSession, process_server_hints};
+use error::{YdbError, check_status};
+
+use generated::ydb::ResultSet;
+use generated::ydb::feature_flag::Status as FeatureFlag;
+use generated::ydb::table::{KeyRange, ReadTableRequest};
+use generated::ydb::table::key_range::{FromBound, ToBound};
+
+/// Settings of [`TableClientWithSession::read_table`]
+#[derive(Debug, Clone)]
+pub struct ReadTableOptions {
+    /// Start of key range: values of first columns of primary key (in order of key).
+    /// Types must match types of key columns, usually they are optional (e.g. `Some(1u64)`)
+    pub from: Bound<Row>,
+    /// End of key range, like `from`
+    pub to: Bound<Row>,
+    /// Columns to read (all columns if empty)
+    pub columns: Vec<String>,
+    /// Max count of rows to read (no limit if zero)
+    pub row_limit: u64,
+    /// Return rows in order of primary key
+    pub ordered: bool,
+    /// Read from consistent snapshot of table (default of server if `None`)
+    pub snapshot: Option<bool>,
+}
+
+impl Default for ReadTableOptions {
+    fn default() -> Self {
+        Self { from: Bound::Unbounded, to: Bound::Unbounded, columns: Vec::new(), row_limit: 0, ordered: false, snapshot: None }
+    }
+}
+
+impl From<ReadTableOptions> for ReadTableRequest {
+    fn from(value: ReadTableOptions) -> Self {
+        let from_bound = match value.from {
+            Bound::Included(key) => Some(FromBound::GreaterOrEqual(key.into_tuple())),
+            Bound::Excluded(key) => Some(FromBound::Greater(key.into_tuple())),
+            Bound::Unbounded => None,
+        };
+        let to_bound = match value.to {
+            Bound::Included(key) => Some(ToBound::LessOrEqual(key.into_tuple())),
+            Bound::Excluded(key) => Some(ToBound::Less(key.into_tuple())),
+            Bound::Unbounded => None,
+        };
+        let key_range = (from_bound.is_some() || to_bound.is_some()).then_some(KeyRange { from_bound, to_bound });
+        let use_snapshot = match value.snapshot {
+            Some(true) => FeatureFlag::Enabled,
+            Some(false) => FeatureFlag::Disabled,
+            None => FeatureFlag::Unspecified,
+        };
+        Self {
+            key_range,
+            columns: value.columns,
+            ordered: value.ordered,
+            row_limit: value.row_limit,
+            use_snapshot: use_snapshot.into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl<'a, C: Credentials + Send> TableClientWithSession<'a, C> {
+    /// Reads table by parts. Status of each part is checked, parts without data are skipped.
+    /// Server hints from trailers of stream (e.g. `session-close`) are processed when stream ends
+    pub async fn read_table(&mut self, table: &str, options: ReadTableOptions) -> Result<impl Stream<Item = Result<ResultSet, YdbError>> + Unpin + 'static, YdbError> {
+        let req = ReadTableRequest { path: table.to_owned(), ..options.into() };
+        let parts = self.stream_read_table(req).await?.into_inner();
+        let session_ref = self.session_ref();
+        let parts = futures::stream::unfold(Some(parts), move |parts| {
+            let session_ref = session_ref.clone();
+            async move {
+                let mut parts = parts?;
+                match parts.message().await {
+                    Ok(Some(part)) => Some((Ok(part), Some(parts))),
+                    Ok(None) => {
+                        if let Ok(Some(trailers)) = parts.trailers().await {
+                            process_server_hints(&trailers, &session_ref);
+                        }
+                        None
+                    }
+                    Err(e) => {
+                        process_server_hints(e.metadata(), &session_ref);
+                        Some((Err(e), None))
+                    }
+                }
+            }
+        });
+        Ok(parts.filter_map(|part| futures::future::ready(match part {
+            Ok(part) => match check_status(part.status, part.issues) {
+                Ok(()) => part.result.and_then(|r| r.result_set).map(Ok),
+                Err(e) => Some(Err(e)),
+            },
+            Err(e) => Some(Err(e.into())),
+        })).boxed())
+    }
+}
+
+#[test]
+fn read_table_request() {
+    use generated::ydb::r#type::Type as T;
+    let options = ReadTableOptions {
+        from: Bound::Excluded(Row::new().set("id", Some(1u64))),
+        row_limit: 10,
+        snapshot: Some(true),
+        ..Default::default()
+    };
+    let req = ReadTableRequest::from(options);
+    assert_eq!((req.row_limit, req.use_snapshot()), (10, FeatureFlag::Enabled));
+    let Some(KeyRange { from_bound: Some(FromBound::Greater(key)), to_bound: None }) = req.key_range else { panic!("greater bound expected") };
+    let Some(T::TupleType(tuple)) = key.r#type.and_then(|t| t.r#type) else { panic!("tuple expected") };
+    assert_eq!(tuple.elements.len(), 1);
+    assert!(ReadTableRequest::from(ReadTableOptions::default()).key_range.is_none());
+}
@@ -0,0 +1,94 @@
+//! Scheme service client to work with directories and other scheme objects
+//!
+//! # Examples
+//! ```rust,no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//!     let mut conn = ydb_unofficial::YdbConnection::from_env();
+//!     let db_name = std::env::var("DB_NAME").unwrap();
+//!     let dir = format!("{db_name}/some/dir");
+//!     let mut scheme = conn.scheme();
+//!     scheme.make_dir(&dir).await.unwrap();
+//!     let entry = scheme.describe(&dir).await.unwrap();
+//!     assert_eq!(entry.name, "dir");
+//!     scheme.remove_directory_recursive(&format!("{db_name}/some")).await.unwrap();
+//! # }
+//! ```
+use super::*;
+use auth::Credentials;
+use client::{delegate, YdbConnection};
+use error::YdbError;
+use payload::YdbResponseWithResult;
+
+use generated::ydb::scheme::v1::scheme_service_client::SchemeServiceClient;
+use generated::ydb::scheme::*;
+use generated::ydb::scheme::entry::Type as EntryType;
+
+/// [`SchemeServiceClient`] wrapper, that checks status of each response.
+/// Use [`YdbConnection::scheme`] to create it
+#[derive(Debug)]
+pub struct SchemeClient<'a, C: Credentials> {
+    client: SchemeServiceClient<&'a mut YdbConnection<C>>,
+}
+
+impl<'a, C: Credentials> SchemeClient<'a, C> {
+    pub(crate) fn new(conn: &'a mut YdbConnection<C>) -> Self {
+        Self { client: SchemeServiceClient::new(conn) }
+    }
+    delegate!{
+        fn make_directory(MakeDirectoryRequest) -> MakeDirectoryResponse;
+        fn remove_directory(RemoveDirectoryRequest) -> RemoveDirectoryResponse;
+        fn list_directory(ListDirectoryRequest) -> ListDirectoryResponse;
+        fn describe_path(DescribePathRequest) -> DescribePathResponse;
+        fn modify_permissions(ModifyPermissionsRequest) -> ModifyPermissionsResponse;
+    }
+    /// Creates directory by path
+    pub async fn make_dir(&mut self, path: &str) -> Result<(), YdbError> {
+        self.make_directory(MakeDirectoryRequest { path: path.to_owned(), ..Default::default() }).await?;
+        Ok(())
+    }
+    /// Removes empty directory by path
+    pub async fn remove_dir(&mut self, path: &str) -> Result<(), YdbError> {
+        self.remove_directory(RemoveDirectoryRequest { path: path.to_owned(), ..Default::default() }).await?;
+        Ok(())
+    }
+    /// Returns child entries of directory
+    pub async fn list(&mut self, path: &str) -> Result<Vec<Entry>, YdbError> {
+        let response = self.list_directory(ListDirectoryRequest { path: path.to_owned(), ..Default::default() }).await?;
+        Ok(response.into_inner().result()?.children)
+    }
+    /// Returns entry of scheme object by path
+    pub async fn describe(&mut self, path: &str) -> Result<Entry, YdbError> {
+        let response = self.describe_path(DescribePathRequest { path: path.to_owned(), ..Default::default() }).await?;
+        response.into_inner().result()?.self_.ok_or(YdbError::EmptyResponse)
+    }
+    /// Walks through directory tree and returns all nested entries with their full paths.
+    /// Parent directory always goes before its children
+    pub async fn list_directory_recursive(&mut self, path: &str) -> Result<Vec<(String, Entry)>, YdbError> {
+        let mut result = Vec::new();
+        let mut dirs = vec![path.trim_end_matches('/').to_owned()];
+        while let Some(dir) = dirs.pop() {
+            for entry in self.list(&dir).await? {
+                let path = format!("{dir}/{}", entry.name);
+                if entry.r#type() == EntryType::Directory {
+                    dirs.push(path.clone());
+                }
+                result.push((path, entry));
+            }
+        }
+        Ok(result)
+    }
+    /// Removes directory with all nested directories.
+    /// Fails if some directory contains another objects (tables, topics, etc.)
+    pub async fn remove_directory_recursive(&mut self, path: &str) -> Result<(), YdbError> {
+        let entries = self.list_directory_recursive(path).await?;
+        let nested = entries.into_iter()
+            .filter(|(_, entry)| entry.r#type() == EntryType::Directory)
+            .map(|(path, _)| path);
+        let dirs: Vec<_> = std::iter::once(path.trim_end_matches('/').to_owned()).chain(nested).collect();
+        for dir in dirs.iter().rev() {
+            self.remove_dir(dir).await?;
+        }
+        Ok(())
+    }
+}
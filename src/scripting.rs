@@ -0,0 +1,103 @@
+//! Scripting service client. Allows to run YQL scripts, that mix scheme (DDL) and data (DML) queries
+//!
+//! # Examples
+//! ```rust,no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//!     let mut conn = ydb_unofficial::YdbConnection::from_env();
+//!     let script = r#"
+//!         create table script_test (id Int32, primary key(id));
+//!         commit;
+//!         upsert into script_test (id) values (1), (2);
+//!         commit;
+//!         select * from script_test;
+//!         drop table script_test;
+//!     "#;
+//!     let result = conn.scripting().execute(script, Default::default()).await.unwrap();
+//!     assert_eq!(result.result_sets[0].rows.len(), 2);
+//! # }
+//! ```
+use std::collections::HashMap;
+
+use futures::{Stream, StreamExt};
+
+use super::*;
+use auth::Credentials;
+use client::{delegate, YdbConnection};
+use error::{YdbError, ErrWithOperation};
+use payload::YdbResponseWithResult;
+
+use generated::ydb::TypedValue;
+use generated::ydb::status_ids::StatusCode;
+use generated::ydb::scripting::v1::scripting_service_client::ScriptingServiceClient;
+use generated::ydb::scripting::*;
+
+/// Query parameters, e.g. `$id` -> `TypedValue`. The same as `YdbArgumentBuffer` in sqlx integration
+pub type Parameters = HashMap<String, TypedValue>;
+
+/// [`ScriptingServiceClient`] wrapper, that checks status of each response.
+/// Use [`YdbConnection::scripting`] to create it
+#[derive(Debug)]
+pub struct ScriptingClient<'a, C: Credentials> {
+    client: ScriptingServiceClient<&'a mut YdbConnection<C>>,
+}
+
+impl<'a, C: Credentials> ScriptingClient<'a, C> {
+    pub(crate) fn new(conn: &'a mut YdbConnection<C>) -> Self {
+        Self { client: ScriptingServiceClient::new(conn) }
+    }
+    delegate!{
+        fn execute_yql(ExecuteYqlRequest) -> ExecuteYqlResponse;
+        fn explain_yql(ExplainYqlRequest) -> ExplainYqlResponse;
+    }
+    pub async fn stream_execute_yql(&mut self, req: ExecuteYqlRequest) -> Result<tonic::Response<tonic::codec::Streaming<ExecuteYqlPartialResponse>>, tonic::Status> {
+        self.client.stream_execute_yql(req).await
+    }
+    /// Executes script with parameters and returns all result sets at once
+    pub async fn execute(&mut self, script: &str, parameters: Parameters) -> Result<ExecuteYqlResult, YdbError> {
+        let req = ExecuteYqlRequest { script: script.to_owned(), parameters, ..Default::default() };
+        let response = self.execute_yql(req).await?;
+        Ok(response.into_inner().result()?)
+    }
+    /// Executes script with parameters and returns stream of result set parts.
+    /// Each part contains index of result set, so parts of one result set can be merged by it.
+    /// Stream stops on first error
+    pub async fn stream(&mut self, script: &str, parameters: Parameters) -> Result<impl Stream<Item = Result<ExecuteYqlPartialResult, YdbError>>, YdbError> {
+        let req = ExecuteYqlRequest { script: script.to_owned(), parameters, ..Default::default() };
+        let stream = self.stream_execute_yql(req).await?.into_inner();
+        Ok(stream.map(|part| {
+            let part = part?;
+            match part.status() {
+                StatusCode::Success => part.result.ok_or(YdbError::EmptyResponse),
+                _ => Err(YdbError::Ydb(ErrWithOperation::from_status(part.status, part.issues))),
+            }
+        }).scan(false, |failed, part| {
+            let result = if *failed { None } else { Some(part) };
+            *failed = matches!(result, Some(Err(_)));
+            futures::future::ready(result)
+        }))
+    }
+}
+
+#[cfg(feature = "sqlx")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sqlx")))]
+mod sqlx_results {
+    use super::*;
+    use crate::sqlx::entities::{YdbQueryResult, YdbResultSet};
+
+    impl<'a, C: Credentials> ScriptingClient<'a, C> {
+        /// Executes script and converts result to [`YdbQueryResult`], so rows can be decoded with sqlx
+        pub async fn query(&mut self, script: &str, parameters: Parameters) -> Result<YdbQueryResult, YdbError> {
+            Ok(self.execute(script, parameters).await?.into())
+        }
+        /// Executes script and returns stream of (result set index, result set part) pairs
+        pub async fn stream_result_sets(&mut self, script: &str, parameters: Parameters) -> Result<impl Stream<Item = Result<(u32, YdbResultSet), YdbError>>, YdbError> {
+            let stream = self.stream(script, parameters).await?;
+            Ok(stream.map(|part| {
+                let ExecuteYqlPartialResult { result_set_index, result_set, .. } = part?;
+                let result_set = result_set.ok_or(YdbError::EmptyResponse)?;
+                Ok((result_set_index, result_set.into()))
+            }))
+        }
+    }
+}
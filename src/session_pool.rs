@@ -0,0 +1,265 @@
+//! Pool of table sessions of one [`YdbConnection`]. Unlike [`YdbConnection::table`] it allows concurrent queries
+//! on one channel: each [`PooledSession`] owns its session and transport, so it can be moved into spawned task.
+//! Session returns to pool on drop. Session is dropped from pool, if server reports `BadSession`, `SessionExpired` or `SessionBusy`,
+//! or sends `session-close` hint (on graceful shutdown of node)
+//!
+//! # Examples
+//! ```rust,no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//!     use ydb_unofficial::session_pool::SessionPoolOptions;
+//!     use ydb_unofficial::generated::ydb::table::{ExecuteDataQueryRequest, Query, query};
+//!     let options = SessionPoolOptions { min_sessions: 2, max_sessions: 10, ..Default::default() };
+//!     let mut conn = ydb_unofficial::YdbConnection::from_env().with_session_pool(options);
+//!     conn.sessions().warm_up().await.unwrap();
+//!     let tasks = (0..4).map(|i| {
+//!         let sessions = conn.sessions();
+//!         tokio::spawn(async move {
+//!             let mut session = sessions.acquire().await.unwrap();
+//!             let query = Some(Query { query: Some(query::Query::YqlText(format!("SELECT {i}"))) });
+//!             session.execute_data_query(ExecuteDataQueryRequest { query, ..Default::default() }).await.unwrap();
+//!         })
+//!     }).collect::<Vec<_>>();
+//!     for task in tasks {
+//!         task.await.unwrap();
+//!     }
+//! # }
+//! ```
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use tokio::sync::{Semaphore, OwnedSemaphorePermit};
+
+use super::*;
+use auth::Credentials;
+use client::{delegate, process_session_fail, process_server_hints, YdbService};
+use error::YdbError;
+use payload::YdbResponseWithResult;
+use keep_alive::KeepAliveOptions;
+
+use generated::ydb::table::*;
+use generated::ydb::table::v1::table_service_client::TableServiceClient;
+
+/// Settings of [`SessionPool`]
+#[derive(Debug, Clone)]
+pub struct SessionPoolOptions {
+    /// Count of sessions, that [`SessionPool::warm_up`] creates
+    pub min_sessions: usize,
+    /// Max count of sessions (idle and in use)
+    pub max_sessions: usize,
+    /// Max time of waiting for free session. Creation of new session is not limited by it, so created session is never lost
+    pub acquire_timeout: Duration,
+}
+
+impl Default for SessionPoolOptions {
+    fn default() -> Self {
+        Self { min_sessions: 0, max_sessions: 50, acquire_timeout: Duration::from_secs(5) }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct IdleSession {
+    pub(crate) id: String,
+    /// Time of last use
+    pub(crate) since: Instant,
+    /// Time of last use or successful keep-alive
+    pub(crate) checked: Instant,
+}
+
+impl IdleSession {
+    pub(crate) fn new(id: String) -> Self {
+        let now = Instant::now();
+        Self { id, since: now, checked: now }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Shared<C: Credentials> {
+    service: YdbService<C>,
+    pub(crate) options: SessionPoolOptions,
+    /// Most recently used sessions are at the back
+    pub(crate) idle: Mutex<VecDeque<IdleSession>>,
+    permits: Arc<Semaphore>,
+}
+
+impl<C: Credentials> Shared<C> {
+    pub(crate) fn service(&self) -> YdbService<C> {
+        self.service.clone()
+    }
+}
+
+impl<C: Credentials> Drop for Shared<C> {
+    fn drop(&mut self) {
+        let idle = std::mem::take(self.idle.get_mut().unwrap());
+        for session in idle {
+            spawn_delete(self.service(), session.id);
+        }
+    }
+}
+
+/// Deletes session in background (if there is runtime)
+pub(crate) fn spawn_delete<C: Credentials>(service: YdbService<C>, session_id: String) {
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else { return };
+    runtime.spawn(async move {
+        let mut client = TableServiceClient::new(service);
+        match client.delete_session(DeleteSessionRequest { session_id: session_id.clone(), ..Default::default() }).await {
+            Ok(_) => log::debug!("Session closed: {session_id}"),
+            Err(e) => log::debug!("Error on closing session ({session_id}): {e}"),
+        }
+    });
+}
+
+pub(crate) async fn create_session<C: Credentials>(service: YdbService<C>) -> Result<String, YdbError> {
+    let mut client = TableServiceClient::new(service);
+    let response = client.create_session(CreateSessionRequest::default()).await?;
+    let session_id = response.into_inner().result()?.session_id;
+    log::debug!("Session created: {session_id}");
+    Ok(session_id)
+}
+
+/// Pool of sessions. Cheap to clone: clones share the same sessions.
+/// Use [`YdbConnection::sessions`] to get it
+#[derive(Debug, Clone)]
+pub struct SessionPool<C: Credentials> {
+    pub(crate) shared: Arc<Shared<C>>,
+}
+
+impl<C: Credentials> SessionPool<C> {
+    pub(crate) fn new(service: YdbService<C>, options: SessionPoolOptions) -> Self {
+        let permits = Arc::new(Semaphore::new(options.max_sessions.max(1)));
+        Self { shared: Arc::new(Shared { service, options, idle: Default::default(), permits }) }
+    }
+    /// Takes idle session or creates new one. Waits for free session, if there are `max_sessions` in use
+    pub async fn acquire(&self) -> Result<PooledSession<C>, YdbError> {
+        let permit = tokio::time::timeout(self.shared.options.acquire_timeout, self.shared.permits.clone().acquire_owned()).await
+            .map_err(|_| YdbError::SessionPoolTimeout)?
+            .expect("semaphore of pool is never closed");
+        let idle = self.shared.idle.lock().unwrap().pop_back();
+        let session_id = match idle {
+            Some(session) => session.id,
+            None => create_session(self.shared.service()).await?,
+        };
+        Ok(PooledSession {
+            session_ref: Arc::new(RwLock::new(Some(session_id.clone()))),
+            session_id,
+            client: TableServiceClient::new(self.shared.service()),
+            pool: self.shared.clone(),
+            _permit: permit,
+        })
+    }
+    /// Creates sessions until pool has `min_sessions` (but no more than `max_sessions`)
+    pub async fn warm_up(&self) -> Result<(), YdbError> {
+        let options = &self.shared.options;
+        while self.idle() + self.in_use() < options.min_sessions.min(options.max_sessions.max(1)) {
+            let id = create_session(self.shared.service()).await?;
+            self.shared.idle.lock().unwrap().push_front(IdleSession::new(id));
+        }
+        Ok(())
+    }
+    /// Count of idle sessions
+    pub fn idle(&self) -> usize {
+        self.shared.idle.lock().unwrap().len()
+    }
+    /// Count of acquired sessions
+    pub fn in_use(&self) -> usize {
+        self.shared.options.max_sessions.max(1) - self.shared.permits.available_permits()
+    }
+    /// Deletes idle sessions. Acquired sessions stay alive
+    pub async fn clear(&self) -> Result<(), YdbError> {
+        let idle = std::mem::take(&mut *self.shared.idle.lock().unwrap());
+        let mut client = TableServiceClient::new(self.shared.service());
+        for session in idle {
+            client.delete_session(DeleteSessionRequest { session_id: session.id, ..Default::default() }).await?;
+        }
+        Ok(())
+    }
+}
+
+impl<C: Credentials + Sync> SessionPool<C> {
+    /// Starts background task, that checks idle sessions with `KeepAlive` and replaces busy or bad ones.
+    /// Task stops when pool is dropped. See [`crate::keep_alive`]
+    pub fn spawn_keep_alive(&self, options: KeepAliveOptions) {
+        keep_alive::spawn_for_pool(Arc::downgrade(&self.shared), options);
+    }
+}
+
+/// Session, taken from [`SessionPool`]. It returns to pool on drop, if it is still valid.
+/// For each method (that requires session_id) it injects session_id field
+#[derive(Debug)]
+pub struct PooledSession<C: Credentials> {
+    session_ref: Arc<RwLock<Option<String>>>,
+    session_id: String,
+    client: TableServiceClient<YdbService<C>>,
+    pool: Arc<Shared<C>>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl<C: Credentials> PooledSession<C> {
+    delegate!{ with session_id:
+        fn create_table(CreateTableRequest) -> CreateTableResponse;
+        fn drop_table(DropTableRequest) -> DropTableResponse;
+        fn alter_table(AlterTableRequest) -> AlterTableResponse;
+        fn copy_table(CopyTableRequest) -> CopyTableResponse;
+        fn rename_tables(RenameTablesRequest) -> RenameTablesResponse;
+        fn describe_table(DescribeTableRequest) -> DescribeTableResponse;
+        fn execute_data_query(ExecuteDataQueryRequest) -> ExecuteDataQueryResponse;
+        fn execute_scheme_query(ExecuteSchemeQueryRequest) -> ExecuteSchemeQueryResponse;
+        fn explain_data_query(ExplainDataQueryRequest) -> ExplainDataQueryResponse;
+        fn prepare_data_query(PrepareDataQueryRequest) -> PrepareDataQueryResponse;
+        fn keep_alive(KeepAliveRequest) -> KeepAliveResponse;
+        fn begin_transaction(BeginTransactionRequest) -> BeginTransactionResponse;
+        fn commit_transaction(CommitTransactionRequest) -> CommitTransactionResponse;
+        fn rollback_transaction(RollbackTransactionRequest) -> RollbackTransactionResponse;
+    }
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+    /// Drops session from pool (and deletes it on server)
+    pub fn invalidate(self) {
+        *self.session_ref.write().unwrap() = None;
+    }
+}
+
+impl<C: Credentials> Drop for PooledSession<C> {
+    fn drop(&mut self) {
+        if self.session_ref.read().unwrap().is_some() {
+            self.pool.idle.lock().unwrap().push_back(IdleSession::new(std::mem::take(&mut self.session_id)));
+        } else {
+            log::debug!("Session {} is dropped from pool", self.session_id);
+            spawn_delete(self.pool.service(), std::mem::take(&mut self.session_id));
+        }
+    }
+}
+
+#[tokio::test]
+async fn acquire_and_release() {
+    let channel = tonic::transport::Endpoint::from_static("http://localhost:1").connect_lazy();
+    let conn = client::YdbConnection::new(channel, "/local".try_into().unwrap(), String::new());
+    let options = SessionPoolOptions { max_sessions: 1, acquire_timeout: Duration::from_millis(50), ..Default::default() };
+    let pool = SessionPool::new(conn.service(), options);
+    pool.shared.idle.lock().unwrap().push_back(IdleSession::new("s1".to_owned()));
+
+    let session = pool.acquire().await.unwrap();
+    assert_eq!((session.session_id(), pool.idle(), pool.in_use()), ("s1", 0, 1));
+    assert!(matches!(pool.acquire().await, Err(YdbError::SessionPoolTimeout)));
+    drop(session);
+    assert_eq!((pool.idle(), pool.in_use()), (1, 0));
+
+    pool.acquire().await.unwrap().invalidate();
+    assert_eq!((pool.idle(), pool.in_use()), (0, 0));
+}
+
+#[tokio::test]
+async fn warm_up_respects_max_sessions() {
+    let channel = tonic::transport::Endpoint::from_static("http://localhost:1").connect_lazy();
+    let conn = client::YdbConnection::new(channel, "/local".try_into().unwrap(), String::new());
+    let options = SessionPoolOptions { min_sessions: 3, max_sessions: 1, ..Default::default() };
+    let pool = SessionPool::new(conn.service(), options);
+    pool.shared.idle.lock().unwrap().push_back(IdleSession::new("s1".to_owned()));
+    //no session is created (server is unavailable), because pool is already full
+    pool.warm_up().await.unwrap();
+    let _session = pool.acquire().await.unwrap();
+    pool.warm_up().await.unwrap();
+    assert_eq!((pool.idle(), pool.in_use()), (0, 1));
+}
@@ -3,7 +3,8 @@ use std::time::Duration;
 use super::YdbError;
 use super::database::Ydb;
 use super::executor::{YdbExecutor, YdbSchemeExecutor};
-use futures::Future;
+use super::entities::{YdbResultSet, YdbRow};
+use futures::{Future, Stream, TryStreamExt};
 use sqlx_core::transaction::{Transaction, TransactionManager};
 use sqlx_core::pool::MaybePoolConnection;
 use tonic::codegen::futures_core::future::BoxFuture;
@@ -16,6 +17,11 @@ use ydb::table::transaction_settings::TxMode;
 use crate::{AsciiValue, YdbTransaction};
 use crate::auth::UpdatableToken;
 use crate::client::YdbEndpoint;
+use crate::read_table::ReadTableOptions;
+use crate::keep_alive::KeepAliveOptions;
+use crate::driver::Driver;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
 
 use crate::payload::YdbResponseWithResult;
 
@@ -54,6 +60,9 @@ pub struct YdbConnectOptions {
     db_name: AsciiValue,
     creds: UpdatableToken,
     log_options: LogOptions,
+    keep_alive: Option<KeepAliveOptions>,
+    /// Shared between clones of options, so connections of sqlx pool use the same driver
+    driver: Option<Arc<OnceCell<Driver<UpdatableToken>>>>,
 }
 
 impl YdbConnectOptions {
@@ -61,6 +70,19 @@ impl YdbConnectOptions {
         self.creds = creds;
         self
     }
+    /// Enables background keep-alive of session of connection, see [`crate::keep_alive`].
+    /// Also it can be enabled with parameter of connection string `keep_alive=<interval in seconds>`
+    pub fn with_keep_alive(mut self, options: KeepAliveOptions) -> Self {
+        self.keep_alive = Some(options);
+        self
+    }
+    /// Connects through [`Driver`]: connections are balanced between nodes of database.
+    /// Also discovery can be enabled with parameter of connection string `discovery=true`
+    /// (then driver is created on first connect)
+    pub fn with_driver(mut self, driver: Driver<UpdatableToken>) -> Self {
+        self.driver = Some(Arc::new(OnceCell::new_with(Some(driver))));
+        self
+    }
 }
 
 impl FromStr for YdbConnectOptions {
@@ -79,6 +101,12 @@ fn test_conn_options_from_str() {
     assert_eq!(options.endpoint.host, "ydb.serverless.yandexcloud.net");
     assert_eq!(options.endpoint.port, 2135);
     assert_eq!(options.db_name.as_bytes(), "/ru-central1/some-anfslndundf908/234ndfnsdkjf".as_bytes());
+    assert!(options.keep_alive.is_none());
+    let options = YdbConnectOptions::from_str("ydbs://localhost:2135/local?keep_alive=20").unwrap();
+    assert_eq!(options.keep_alive.map(|k| k.interval), Some(Duration::from_secs(20)));
+    assert!(options.driver.is_none());
+    let options = YdbConnectOptions::from_str("ydbs://localhost:2135/local?discovery=true").unwrap();
+    assert!(options.driver.is_some_and(|d| !d.initialized()));
 }
 
 fn default_tx_control() -> TransactionControl {
@@ -106,8 +134,18 @@ impl ConnectOptions for YdbConnectOptions {
         let mut db_name = url.path().try_into().map_err(|e|ConfErr(format!("cannot parse database name: {e}").into()))?;
         let endpoint = YdbEndpoint { ssl, host, port, load_factor: 0.0 };
         let mut creds = UpdatableToken::new("".try_into().unwrap());
+        let mut keep_alive = None;
+        let mut driver = None;
         for (k,v) in url.query_pairs() {
             match k.as_ref() {
+                "discovery" => {
+                    let enabled: bool = v.parse().map_err(|e|ConfErr(format!("cannot parse discovery flag: {e}").into()))?;
+                    driver = enabled.then(Default::default);
+                }
+                "keep_alive" => {
+                    let secs = v.parse().map_err(|e|ConfErr(format!("cannot parse keep_alive interval: {e}").into()))?;
+                    keep_alive = Some(KeepAliveOptions { interval: Duration::from_secs(secs), ..Default::default() });
+                }
                 "database" => {
                     db_name = v.as_ref().try_into().map_err(|e|ConfErr(format!("cannot parse database name: {e}").into()))?;
                 }
@@ -130,17 +168,28 @@ impl ConnectOptions for YdbConnectOptions {
                 _ => {}
             }
         };
-        Ok(Self{endpoint, db_name, creds, log_options: Default::default()})
+        Ok(Self{endpoint, db_name, creds, log_options: Default::default(), keep_alive, driver})
     }
 
     fn connect(&self) -> BoxFuture<'_, Result<Self::Connection, sqlx_core::Error>>
     where
-        Self::Connection: Sized { //TODO: реализовать подключение к разным эндпойнтам из discovery (чтобы pool подключался как надо)
-        let channel = self.endpoint.make_endpoint().connect_lazy();
-        let mut inner = crate::YdbConnection::new(channel, self.db_name.clone(), self.creds.clone());
+        Self::Connection: Sized {
         let tx_control = default_tx_control();
         let log_options = self.log_options;
         Box::pin(async move {
+            let inner = match &self.driver {
+                Some(driver) => driver.get_or_try_init(|| {
+                    Driver::new(self.creds.clone(), self.db_name.clone(), self.endpoint.clone(), Default::default())
+                }).await?.connection(),
+                None => {
+                    let channel = self.endpoint.make_endpoint().connect_lazy();
+                    crate::YdbConnection::new(channel, self.db_name.clone(), self.creds.clone())
+                }
+            };
+            let mut inner = match &self.keep_alive {
+                Some(options) => inner.with_keep_alive(options.clone()),
+                None => inner,
+            };
             let _ = inner.table().await?;
             Ok(YdbConnection { inner, options: self.clone(), tx_control, log_options, retry: true })
         })
@@ -199,11 +248,12 @@ impl Connection for YdbConnection {
 }
 
 impl YdbConnection {
-    /// Retrieve DML executor, that can select/insert/update values in existing tables, but cannot modify their definitions
+    /// Retrieve DML executor, that can select/insert/update values in existing tables, but cannot modify their definitions.
+    /// If connection has no session (e.g. server asked to close it), executor creates new one before query
     pub fn executor(&mut self) -> Result<YdbExecutor<'_>, YdbError> {
         let tx_control = self.tx_control.clone();
         let log_options = self.log_options;
-        let table = self.inner.table_if_ready().ok_or(YdbError::NoSession)?;
+        let table = self.inner.table_lazy();
         let inner = YdbTransaction::new(table, tx_control);
         Ok(YdbExecutor {retry: self.retry, inner, log_options })
     }
@@ -211,9 +261,26 @@ impl YdbConnection {
     /// Note that DDL executor cannot fetch results, prepare and describe (never can used in sqlx macro). Parameter binding also unavailable
     pub fn scheme_executor(&mut self) -> Result<YdbSchemeExecutor<'_>, YdbError> {
         let log_options = self.log_options;
-        let inner = self.inner.table_if_ready().ok_or(YdbError::NoSession)?;
+        let inner = self.inner.table_lazy();
         Ok(YdbSchemeExecutor{ inner, log_options })
     }
+    /// Checks health of database. Unlike [`Connection::ping`] it reports status of whole database, see [`crate::monitoring`]
+    pub async fn health(&mut self) -> Result<crate::monitoring::HealthReport, YdbError> {
+        self.inner.health().await
+    }
+    /// Upserts rows into table without transaction, see [`crate::client::TableClientWithSession::bulk_upsert`]
+    pub async fn bulk_upsert(&mut self, table: &str, rows: impl IntoIterator<Item = crate::bulk::Row>) -> Result<(), YdbError> {
+        crate::bulk::upsert_rows(self.inner.service(), table, rows).await
+    }
+    /// Reads rows of table by primary keys without YQL query, see [`crate::read_rows`]
+    pub async fn read_rows(&mut self, table: &str, keys: impl IntoIterator<Item = crate::bulk::Row>, columns: &[&str]) -> Result<YdbResultSet, YdbError> {
+        Ok(self.inner.read_rows(table, keys, columns).await?.into())
+    }
+    /// Reads table (or range of keys) without YQL query, see [`crate::read_table`]
+    pub async fn read_table(&mut self, table: &str, options: ReadTableOptions) -> Result<impl Stream<Item = Result<YdbRow, YdbError>> + 'static, YdbError> {
+        let parts = self.inner.table().await?.read_table(table, options).await?;
+        Ok(parts.map_ok(|part| futures::stream::iter(YdbResultSet::from(part).to_rows().into_iter().map(Ok))).try_flatten())
+    }
     /// Reconnect to Ydb if received [YdbError::NoSession] received
     /// Sometimes Ydb service can invalidate connection with Session. An if you use single connection, you need to reconnect them
     pub async fn reconnect(&mut self) -> Result<(), sqlx_core::Error> {
@@ -252,4 +319,73 @@ impl TransactionManager for YdbTransactionManager {
         conn.tx_control = default_tx_control();
         log::error!("start_rollback method is unimplemented");
     }
-}
\ No newline at end of file
+}
+#[tokio::test]
+async fn executor_recreates_closed_session() {
+    use std::convert::Infallible;
+    use std::sync::Mutex;
+    use std::task::{Context, Poll};
+    use tonic::codegen::{http, Body, Service, StdError};
+    use tonic::server::{Grpc, NamedService};
+    use tonic::codec::ProstCodec;
+    use prost::Message;
+    use ydb::operations::Operation;
+    use ydb::status_ids::StatusCode;
+    use ydb::table::*;
+    use crate::generated::google::protobuf::Any;
+
+    /// Table service, that asks to close session after each query and records sessions of queries
+    #[derive(Clone, Default)]
+    struct Mock { created: Arc<Mutex<usize>>, queries: Arc<Mutex<Vec<String>>> }
+    impl NamedService for Mock {
+        const NAME: &'static str = "Ydb.Table.V1.TableService";
+    }
+    fn operation(result: impl Message) -> Option<Operation> {
+        let result = Some(Any { type_url: String::new(), value: result.encode_to_vec() });
+        Some(Operation { ready: true, status: StatusCode::Success.into(), result, ..Default::default() })
+    }
+    impl<B: Body + Send + 'static> Service<http::Request<B>> for Mock where B::Error: Into<StdError> + Send {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = Infallible;
+        type Future = BoxFuture<'static, Result<Self::Response, Infallible>>;
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            let mock = self.clone();
+            match req.uri().path() {
+                "/Ydb.Table.V1.TableService/CreateSession" => Box::pin(async move {
+                    let service = tower::service_fn(move |_: tonic::Request<CreateSessionRequest>| {
+                        let mut created = mock.created.lock().unwrap();
+                        *created += 1;
+                        let operation = operation(CreateSessionResult { session_id: format!("s{created}") });
+                        async move { Ok(tonic::Response::new(CreateSessionResponse { operation })) }
+                    });
+                    Ok(Grpc::new(ProstCodec::default()).unary(service, req).await)
+                }),
+                "/Ydb.Table.V1.TableService/ExecuteDataQuery" => Box::pin(async move {
+                    let service = tower::service_fn(move |req: tonic::Request<ExecuteDataQueryRequest>| {
+                        mock.queries.lock().unwrap().push(req.into_inner().session_id);
+                        let mut response = tonic::Response::new(ExecuteDataQueryResponse { operation: operation(ExecuteQueryResult::default()) });
+                        response.metadata_mut().insert("x-ydb-server-hints", AsciiValue::from_static("session-close"));
+                        async move { Ok(response) }
+                    });
+                    Ok(Grpc::new(ProstCodec::default()).unary(service, req).await)
+                }),
+                path => panic!("unexpected request {path}"),
+            }
+        }
+    }
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let incoming = tonic::transport::server::TcpIncoming::from_listener(listener, true, None).unwrap();
+    let mock = Mock::default();
+    tokio::spawn(tonic::transport::Server::builder().add_service(mock.clone()).serve_with_incoming(incoming));
+
+    let mut conn = YdbConnectOptions::from_str(&format!("ydb://127.0.0.1:{port}/local")).unwrap().connect().await.unwrap();
+    sqlx_core::query::query("SELECT 1").execute(conn.executor().unwrap()).await.unwrap();
+    //session is closed by server hint, so the next query runs in new session
+    sqlx_core::query::query("SELECT 1").execute(conn.executor().unwrap()).await.unwrap();
+    assert_eq!(*mock.queries.lock().unwrap(), ["s1", "s2"]);
+}
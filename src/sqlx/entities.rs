@@ -15,6 +15,7 @@ use ydb::table_stats::QueryStats;
 use ydb::Column;
 use ydb::ResultSet;
 use ydb::table::ExecuteQueryResult;
+use ydb::scripting::ExecuteYqlResult;
 
 use super::database::Ydb;
 
@@ -168,6 +169,14 @@ impl From<ExecuteQueryResult> for YdbQueryResult {
     }
 }
 
+impl From<ExecuteYqlResult> for YdbQueryResult {
+    fn from(result: ExecuteYqlResult) -> Self {
+        let ExecuteYqlResult {query_stats, result_sets } = result;
+        let result_sets = result_sets.into_iter().map(Into::into).collect();
+        Self { query_stats, result_sets }
+    }
+}
+
 impl From<ResultSet> for YdbResultSet {
     fn from(rs: ResultSet) -> Self {
         let ResultSet {columns, rows, ..} = rs;
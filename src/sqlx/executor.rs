@@ -48,6 +48,7 @@ impl<'c> YdbExecutor<'c> {
         self
     }
     pub async fn send(&mut self, req: ExecuteDataQueryRequest) -> Result<YdbQueryResult, YdbError> {
+        self.inner.table_client().ensure_session().await?;
         let log_msg = format!("Running sql: {:?}", req.query);
         let fut = self.inner.execute_data_query(req);
         let response = self.log_options.wrap(&log_msg, fut).await?;
@@ -112,6 +113,7 @@ impl<'c> Executor<'c> for YdbExecutor<'c> {
 
     fn prepare<'e, 'q: 'e>(mut self, sql: &'q str) -> BoxFuture<'e, Result<YdbStatement, sqlx_core::Error>>
     where 'c: 'e {Box::pin(async move {
+        self.inner.table_client().ensure_session().await?;
         let yql_text = sql.to_owned();
         let msg = format!("Prepare YQL statement: {}", sql);
         let fut = self.inner.table_client().prepare_data_query(PrepareDataQueryRequest{yql_text, ..Default::default()});
@@ -164,6 +166,7 @@ impl<'c> Executor<'c> for YdbExecutor<'c> {
     //TODO: спрятать под фичу
     fn describe<'e, 'q: 'e>(mut self, sql: &'q str) -> BoxFuture<'e, Result<Describe<Ydb>, sqlx_core::Error>>
     where 'c: 'e { Box::pin( async move {
+        self.inner.table_client().ensure_session().await?;
         let response = self.inner.table_client().explain_data_query(ExplainDataQueryRequest{ yql_text: sql.to_owned(), ..Default::default() }).await?;
         let result = response.into_inner().result().map_err(YdbError::from)?;
         let (_, mut node) = super::minikql::Node::parse(&result.query_ast).map_err(|_|YdbError::DecodeAst)?;
@@ -195,6 +198,7 @@ impl <'c> Executor<'c> for YdbSchemeExecutor<'c> {
         let yql_text = query.sql().to_owned();
         let msg = format!("Run YDB scheme statement: {yql_text}");
         Box::pin(async move {
+            self.inner.ensure_session().await?;
             let fut = self.inner.execute_scheme_query(ExecuteSchemeQueryRequest{ yql_text, ..Default::default()});
             self.log_options.wrap(&msg, fut).await?;
             Ok(Default::default())
@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::{Stream, StreamExt};
+use serde_json::Value as Json;
+
+use crate::error::YdbError;
+use crate::generated::ydb::value::Value;
+use super::{TopicReader, ReadBatch, CommitHandle};
+
+/// Values of columns by name
+pub type Row = HashMap<String, Value>;
+
+/// Virtual timestamp of change: plan step and transaction id
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct VirtualTimestamp {
+    pub plan_step: u64,
+    pub tx_id: u64,
+}
+
+/// Change of one row. Filled fields depend on mode of changefeed
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Change {
+    /// Values of primary key columns
+    pub key: Vec<Value>,
+    /// Changed columns (mode `UPDATES`)
+    pub update: Row,
+    /// Row after change (modes `NEW_IMAGE` and `NEW_AND_OLD_IMAGES`)
+    pub new_image: Option<Row>,
+    /// Row before change (modes `OLD_IMAGE` and `NEW_AND_OLD_IMAGES`)
+    pub old_image: Option<Row>,
+    /// Time of change (if virtual timestamps are enabled)
+    pub ts: Option<VirtualTimestamp>,
+}
+
+/// Event of changefeed
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeEvent {
+    /// Row was inserted or updated
+    Upsert(Change),
+    /// Row was deleted
+    Erase(Change),
+    /// All changes up to this timestamp are already delivered (if resolved timestamps are enabled)
+    Resolved(VirtualTimestamp),
+}
+
+fn invalid(message: impl ToString) -> YdbError {
+    YdbError::ChangeRecord(message.to_string())
+}
+
+/// Converts JSON value to value of YDB. Type of column is not known, so integers become `Int64Value`
+/// (or `Uint64Value` if it doesn't fit), and objects or arrays (e.g. of `Json` columns) become text
+fn value(json: Json) -> Value {
+    match json {
+        Json::Null => Value::NullFlagValue(0),
+        Json::Bool(b) => Value::BoolValue(b),
+        Json::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => Value::Int64Value(i),
+            (None, Some(u)) => Value::Uint64Value(u),
+            _ => Value::DoubleValue(n.as_f64().unwrap_or_default()),
+        },
+        Json::String(s) => Value::TextValue(s),
+        json => Value::TextValue(json.to_string()),
+    }
+}
+
+fn row(json: Json) -> Result<Row, YdbError> {
+    match json {
+        Json::Object(columns) => Ok(columns.into_iter().map(|(name, v)| (name, value(v))).collect()),
+        json => Err(invalid(format!("row must be an object: {json}"))),
+    }
+}
+
+fn timestamp(json: Json) -> Result<VirtualTimestamp, YdbError> {
+    match json.as_array().map(Vec::as_slice) {
+        Some([step, tx]) => match (step.as_u64(), tx.as_u64()) {
+            (Some(plan_step), Some(tx_id)) => Ok(VirtualTimestamp { plan_step, tx_id }),
+            _ => Err(invalid(format!("invalid timestamp: {json}"))),
+        },
+        _ => Err(invalid(format!("invalid timestamp: {json}"))),
+    }
+}
+
+impl ChangeEvent {
+    /// Parses JSON record of changefeed
+    pub fn parse(data: &[u8]) -> Result<Self, YdbError> {
+        let Json::Object(mut record) = serde_json::from_slice(data).map_err(invalid)? else {
+            return Err(invalid("record must be an object"));
+        };
+        if let Some(resolved) = record.remove("resolved") {
+            return Ok(Self::Resolved(timestamp(resolved)?));
+        }
+        let key = match record.remove("key") {
+            Some(Json::Array(key)) => key.into_iter().map(value).collect(),
+            _ => return Err(invalid("record has no key")),
+        };
+        let erase = record.remove("erase").is_some();
+        let change = Change {
+            key,
+            update: record.remove("update").map(row).transpose()?.unwrap_or_default(),
+            new_image: record.remove("newImage").map(row).transpose()?,
+            old_image: record.remove("oldImage").map(row).transpose()?,
+            ts: record.remove("ts").map(timestamp).transpose()?,
+        };
+        //images don't tell about erase: in mode OLD_IMAGE every record has only old image
+        if erase {
+            Ok(Self::Erase(change))
+        } else {
+            Ok(Self::Upsert(change))
+        }
+    }
+}
+
+/// Events of one read batch. Malformed records don't break batch: each record is parsed separately,
+/// so batch can be committed anyway
+#[derive(Debug)]
+pub struct ChangeBatch {
+    pub events: Vec<Result<ChangeEvent, YdbError>>,
+    commit: CommitHandle,
+}
+
+impl ChangeBatch {
+    /// Returns handle to commit batch later (e.g. from another task)
+    pub fn commit_handle(&self) -> CommitHandle {
+        self.commit.clone()
+    }
+    /// Commits batch and waits for confirmation of server
+    pub async fn commit(&self) -> Result<(), YdbError> {
+        self.commit.clone().commit().await
+    }
+}
+
+impl From<ReadBatch> for ChangeBatch {
+    fn from(batch: ReadBatch) -> Self {
+        let commit = batch.commit_handle();
+        let events = batch.messages.iter().map(|m| ChangeEvent::parse(&m.data)).collect();
+        Self { events, commit }
+    }
+}
+
+/// Stream of typed events from changefeed of table (feature `cdc`)
+///
+/// # Examples
+/// ```rust,no_run
+/// # #[tokio::main]
+/// # async fn main() {
+///     use futures::StreamExt;
+///     use ydb_unofficial::topic::{ReaderOptions, ChangeEvent};
+///     let mut conn = ydb_unofficial::YdbConnection::from_env();
+///     let options = ReaderOptions { consumer: "cache".to_owned(), ..Default::default() };
+///     let mut changes = conn.topic().changefeed("users", "updates", options).await.unwrap();
+///     while let Some(batch) = changes.next().await {
+///         let batch = batch.unwrap();
+///         for event in &batch.events {
+///             match event {
+///                 Ok(ChangeEvent::Upsert(change)) => println!("upsert {:?}", change.key),
+///                 Ok(ChangeEvent::Erase(change)) => println!("erase {:?}", change.key),
+///                 Ok(ChangeEvent::Resolved(_)) => {}
+///                 Err(e) => println!("malformed record: {e}"),
+///             }
+///         }
+///         batch.commit().await.unwrap();
+///     }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ChangefeedReader {
+    reader: TopicReader,
+}
+
+impl ChangefeedReader {
+    pub(crate) fn new(reader: TopicReader) -> Self {
+        Self { reader }
+    }
+}
+
+impl Stream for ChangefeedReader {
+    type Item = Result<ChangeBatch, YdbError>;
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.reader.poll_next_unpin(cx).map(|batch| batch.map(|batch| batch.map(ChangeBatch::from)))
+    }
+}
+
+#[test]
+fn parse_change_records() {
+    let event = ChangeEvent::parse(br#"{"key":[1,"a"],"update":{"name":"x","age":null},"ts":[10,20]}"#).unwrap();
+    let ChangeEvent::Upsert(change) = event else { panic!("upsert expected") };
+    assert_eq!(change.key, vec![Value::Int64Value(1), Value::TextValue("a".to_owned())]);
+    assert_eq!(change.update["name"], Value::TextValue("x".to_owned()));
+    assert_eq!(change.update["age"], Value::NullFlagValue(0));
+    assert_eq!(change.ts, Some(VirtualTimestamp { plan_step: 10, tx_id: 20 }));
+
+    let event = ChangeEvent::parse(br#"{"key":[18446744073709551615],"erase":{}}"#).unwrap();
+    assert!(matches!(event, ChangeEvent::Erase(c) if c.key == vec![Value::Uint64Value(u64::MAX)]));
+
+    //update in mode OLD_IMAGE
+    let event = ChangeEvent::parse(br#"{"key":[1],"oldImage":{"v":1.5}}"#).unwrap();
+    assert!(matches!(event, ChangeEvent::Upsert(c) if c.old_image.as_ref().unwrap()["v"] == Value::DoubleValue(1.5)));
+
+    let event = ChangeEvent::parse(br#"{"key":[1],"erase":{},"oldImage":{"v":1.5}}"#).unwrap();
+    assert!(matches!(event, ChangeEvent::Erase(c) if c.old_image.is_some()));
+
+    let event = ChangeEvent::parse(br#"{"key":[1],"newImage":{"v":{"a":1}},"oldImage":{"v":null}}"#).unwrap();
+    assert!(matches!(event, ChangeEvent::Upsert(c) if c.new_image.as_ref().unwrap()["v"] == Value::TextValue(r#"{"a":1}"#.to_owned())));
+
+    let event = ChangeEvent::parse(br#"{"resolved":[5,6]}"#).unwrap();
+    assert_eq!(event, ChangeEvent::Resolved(VirtualTimestamp { plan_step: 5, tx_id: 6 }));
+
+    assert!(ChangeEvent::parse(br#"{"update":{}}"#).is_err());
+}
@@ -0,0 +1,69 @@
+#[cfg(feature = "gzip")]
+use std::io::Read;
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+use std::io::Write;
+
+use crate::error::YdbError;
+
+pub use crate::generated::ydb::topic::Codec;
+
+fn unsupported(codec: Codec) -> YdbError {
+    YdbError::Codec(format!("{} is not supported, check features of crate", codec.as_str_name()))
+}
+
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+fn io_error(e: std::io::Error) -> YdbError {
+    YdbError::Codec(e.to_string())
+}
+
+/// Compresses message body with codec. Gzip needs feature `gzip`, zstd needs feature `zstd`
+pub(crate) fn encode(codec: Codec, data: Vec<u8>) -> Result<Vec<u8>, YdbError> {
+    match codec {
+        Codec::Raw | Codec::Unspecified => Ok(data),
+        #[cfg(feature = "gzip")]
+        Codec::Gzip => {
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(&data).map_err(io_error)?;
+            encoder.finish().map_err(io_error)
+        }
+        #[cfg(feature = "zstd")]
+        Codec::Zstd => {
+            let mut encoder = zstd::Encoder::new(Vec::new(), 0).map_err(io_error)?;
+            encoder.write_all(&data).map_err(io_error)?;
+            encoder.finish().map_err(io_error)
+        }
+        codec => Err(unsupported(codec)),
+    }
+}
+
+/// Decompresses message body, received from server
+pub(crate) fn decode(codec: Codec, data: Vec<u8>) -> Result<Vec<u8>, YdbError> {
+    match codec {
+        Codec::Raw | Codec::Unspecified => Ok(data),
+        #[cfg(feature = "gzip")]
+        Codec::Gzip => {
+            let mut decoded = Vec::new();
+            flate2::read::GzDecoder::new(data.as_slice()).read_to_end(&mut decoded).map_err(io_error)?;
+            Ok(decoded)
+        }
+        #[cfg(feature = "zstd")]
+        Codec::Zstd => zstd::decode_all(data.as_slice()).map_err(io_error),
+        codec => Err(unsupported(codec)),
+    }
+}
+
+#[test]
+fn codec_roundtrip() {
+    let data = b"some message, some message, some message".to_vec();
+    #[allow(unused_mut)]
+    let mut codecs = vec![Codec::Raw];
+    #[cfg(feature = "gzip")]
+    codecs.push(Codec::Gzip);
+    #[cfg(feature = "zstd")]
+    codecs.push(Codec::Zstd);
+    for codec in codecs {
+        let encoded = encode(codec, data.clone()).unwrap();
+        assert_eq!(decode(codec, encoded).unwrap(), data);
+    }
+    assert!(encode(Codec::Lzop, data).is_err());
+}
@@ -0,0 +1,255 @@
+//! Topic service client: writing and reading messages of persistent queues, administration of topics.
+//! Writer and reader are background tasks, that don't borrow connection (see [`TopicWriter`] and [`TopicReader`])
+//!
+//! # Examples
+//! ```rust,no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//!     use std::time::Duration;
+//!     use ydb_unofficial::generated::ydb::topic::Consumer;
+//!     use ydb_unofficial::topic::{TopicSettings, TopicAlteration};
+//!     let mut conn = ydb_unofficial::YdbConnection::from_env();
+//!     let mut topic = conn.topic();
+//!     let settings = TopicSettings {
+//!         min_active_partitions: 4,
+//!         retention_period: Some(Duration::from_secs(24 * 3600)),
+//!         consumers: vec![Consumer { name: "my-consumer".to_owned(), ..Default::default() }],
+//!         ..Default::default()
+//!     };
+//!     topic.create("events", settings).await.unwrap();
+//!     let alteration = TopicAlteration { drop_consumers: vec!["my-consumer".to_owned()], ..Default::default() };
+//!     topic.alter("events", alteration).await.unwrap();
+//!     let description = topic.describe("events", true).await.unwrap();
+//!     println!("partitions: {}", description.partitions.len());
+//!     topic.drop("events").await.unwrap();
+//! # }
+//! ```
+mod codec;
+mod writer;
+mod reader;
+#[cfg(feature = "cdc")]
+mod changefeed;
+
+pub use codec::Codec;
+pub use writer::*;
+pub use reader::*;
+#[cfg(feature = "cdc")]
+pub use changefeed::*;
+
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::*;
+use auth::Credentials;
+use client::{delegate, YdbConnection, YdbService};
+use error::{YdbError, ErrWithOperation};
+use payload::YdbResponseWithResult;
+use generated::google::protobuf::{self, Timestamp};
+use generated::ydb::status_ids::StatusCode;
+use generated::ydb::topic::v1::topic_service_client::TopicServiceClient;
+use generated::ydb::topic::*;
+
+/// Settings of new topic
+#[derive(Debug, Clone)]
+pub struct TopicSettings {
+    pub min_active_partitions: i64,
+    /// Max count of partitions (zero means default of server)
+    pub partition_count_limit: i64,
+    /// How long messages are stored (default of server if `None`)
+    pub retention_period: Option<Duration>,
+    /// Max size of partition, old messages are deleted when it exceeded (zero means no limit)
+    pub retention_storage_mb: i64,
+    /// Codecs, that writers may use (all codecs if empty)
+    pub supported_codecs: Vec<Codec>,
+    pub partition_write_speed_bytes_per_second: i64,
+    pub partition_write_burst_bytes: i64,
+    pub attributes: HashMap<String, String>,
+    pub consumers: Vec<Consumer>,
+}
+
+impl Default for TopicSettings {
+    fn default() -> Self {
+        Self {
+            min_active_partitions: 1,
+            partition_count_limit: 0,
+            retention_period: None,
+            retention_storage_mb: 0,
+            supported_codecs: Vec::new(),
+            partition_write_speed_bytes_per_second: 0,
+            partition_write_burst_bytes: 0,
+            attributes: HashMap::new(),
+            consumers: Vec::new(),
+        }
+    }
+}
+
+/// Changes of existing topic. Fields with `None` are not changed
+#[derive(Debug, Clone, Default)]
+pub struct TopicAlteration {
+    pub min_active_partitions: Option<i64>,
+    pub partition_count_limit: Option<i64>,
+    pub retention_period: Option<Duration>,
+    pub retention_storage_mb: Option<i64>,
+    pub supported_codecs: Option<Vec<Codec>>,
+    pub partition_write_speed_bytes_per_second: Option<i64>,
+    pub partition_write_burst_bytes: Option<i64>,
+    /// Attributes to set (empty value removes attribute)
+    pub attributes: HashMap<String, String>,
+    pub add_consumers: Vec<Consumer>,
+    pub drop_consumers: Vec<String>,
+}
+
+fn codecs(codecs: Vec<Codec>) -> Option<SupportedCodecs> {
+    Some(SupportedCodecs { codecs: codecs.into_iter().map(|c| c as i32).collect() })
+}
+
+impl From<TopicSettings> for CreateTopicRequest {
+    fn from(value: TopicSettings) -> Self {
+        Self {
+            partitioning_settings: Some(PartitioningSettings {
+                min_active_partitions: value.min_active_partitions,
+                partition_count_limit: value.partition_count_limit,
+            }),
+            retention_period: value.retention_period.map(proto_duration),
+            retention_storage_mb: value.retention_storage_mb,
+            supported_codecs: codecs(value.supported_codecs),
+            partition_write_speed_bytes_per_second: value.partition_write_speed_bytes_per_second,
+            partition_write_burst_bytes: value.partition_write_burst_bytes,
+            attributes: value.attributes,
+            consumers: value.consumers,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<TopicAlteration> for AlterTopicRequest {
+    fn from(value: TopicAlteration) -> Self {
+        let alter_partitioning_settings = (value.min_active_partitions.is_some() || value.partition_count_limit.is_some()).then(|| {
+            AlterPartitioningSettings {
+                set_min_active_partitions: value.min_active_partitions.unwrap_or_default(),
+                set_partition_count_limit: value.partition_count_limit.unwrap_or_default(),
+            }
+        });
+        Self {
+            alter_partitioning_settings,
+            set_retention_period: value.retention_period.map(proto_duration),
+            set_retention_storage_mb: value.retention_storage_mb.unwrap_or_default(),
+            set_supported_codecs: value.supported_codecs.and_then(codecs),
+            set_partition_write_speed_bytes_per_second: value.partition_write_speed_bytes_per_second.unwrap_or_default(),
+            set_partition_write_burst_bytes: value.partition_write_burst_bytes.unwrap_or_default(),
+            alter_attributes: value.attributes,
+            add_consumers: value.add_consumers,
+            drop_consumers: value.drop_consumers,
+            ..Default::default()
+        }
+    }
+}
+
+/// [`TopicServiceClient`] wrapper, that checks status of each response. Also it starts writers and readers.
+/// Use [`YdbConnection::topic`] to create it
+#[derive(Debug)]
+pub struct TopicClient<'a, C: Credentials> {
+    service: YdbService<C>,
+    client: TopicServiceClient<&'a mut YdbConnection<C>>,
+}
+
+impl<'a, C: Credentials> TopicClient<'a, C> {
+    pub(crate) fn new(conn: &'a mut YdbConnection<C>) -> Self {
+        let service = conn.service();
+        Self { service, client: TopicServiceClient::new(conn) }
+    }
+    delegate!{
+        fn create_topic(CreateTopicRequest) -> CreateTopicResponse;
+        fn describe_topic(DescribeTopicRequest) -> DescribeTopicResponse;
+        fn describe_consumer(DescribeConsumerRequest) -> DescribeConsumerResponse;
+        fn alter_topic(AlterTopicRequest) -> AlterTopicResponse;
+        fn drop_topic(DropTopicRequest) -> DropTopicResponse;
+    }
+    pub async fn create(&mut self, path: &str, settings: TopicSettings) -> Result<(), YdbError> {
+        let req = CreateTopicRequest { path: path.to_owned(), ..settings.into() };
+        self.create_topic(req).await?;
+        Ok(())
+    }
+    pub async fn alter(&mut self, path: &str, alteration: TopicAlteration) -> Result<(), YdbError> {
+        let req = AlterTopicRequest { path: path.to_owned(), ..alteration.into() };
+        self.alter_topic(req).await?;
+        Ok(())
+    }
+    pub async fn drop(&mut self, path: &str) -> Result<(), YdbError> {
+        self.drop_topic(DropTopicRequest { path: path.to_owned(), ..Default::default() }).await?;
+        Ok(())
+    }
+    /// Describes topic with its partitions and consumers. Statistics of partitions are filled if `include_stats` is set
+    pub async fn describe(&mut self, path: &str, include_stats: bool) -> Result<DescribeTopicResult, YdbError> {
+        let req = DescribeTopicRequest { path: path.to_owned(), include_stats, ..Default::default() };
+        let response = self.describe_topic(req).await?;
+        Ok(response.into_inner().result()?)
+    }
+    pub async fn add_consumer(&mut self, path: &str, consumer: Consumer) -> Result<(), YdbError> {
+        self.alter(path, TopicAlteration { add_consumers: vec![consumer], ..Default::default() }).await
+    }
+    pub async fn drop_consumer(&mut self, path: &str, consumer: &str) -> Result<(), YdbError> {
+        self.alter(path, TopicAlteration { drop_consumers: vec![consumer.to_owned()], ..Default::default() }).await
+    }
+    /// Describes consumer with its read positions in partitions (if `include_stats` is set)
+    pub async fn consumer(&mut self, path: &str, consumer: &str, include_stats: bool) -> Result<DescribeConsumerResult, YdbError> {
+        let req = DescribeConsumerRequest { path: path.to_owned(), consumer: consumer.to_owned(), include_stats, ..Default::default() };
+        let response = self.describe_consumer(req).await?;
+        Ok(response.into_inner().result()?)
+    }
+    /// Starts writer into topic by path
+    pub async fn writer(&self, path: &str, options: WriterOptions) -> Result<TopicWriter, YdbError> {
+        TopicWriter::start(self.service.clone(), path, options).await
+    }
+    /// Starts reader of topics with consumer from `options`
+    pub async fn reader(&self, topics: &[&str], options: ReaderOptions) -> Result<TopicReader, YdbError> {
+        TopicReader::start(self.service.clone(), topics, options).await
+    }
+    /// Starts reader of changefeed of table. Consumer from `options` must be added to changefeed topic (feature `cdc`)
+    #[cfg(feature = "cdc")]
+    pub async fn changefeed(&self, table: &str, changefeed: &str, options: ReaderOptions) -> Result<ChangefeedReader, YdbError> {
+        let topic = format!("{table}/{changefeed}");
+        let reader = TopicReader::start(self.service.clone(), &[&topic], options).await?;
+        Ok(ChangefeedReader::new(reader))
+    }
+}
+
+fn proto_duration(duration: Duration) -> protobuf::Duration {
+    protobuf::Duration { seconds: duration.as_secs() as i64, nanos: duration.subsec_nanos() as i32 }
+}
+
+fn timestamp(time: SystemTime) -> Timestamp {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    Timestamp { seconds: since_epoch.as_secs() as i64, nanos: since_epoch.subsec_nanos() as i32 }
+}
+
+fn system_time(timestamp: Option<Timestamp>) -> SystemTime {
+    let timestamp = timestamp.unwrap_or_default();
+    UNIX_EPOCH + Duration::new(timestamp.seconds.max(0) as u64, timestamp.nanos.max(0) as u32)
+}
+
+/// Stream can be restored after these errors
+fn retryable(e: &YdbError) -> bool {
+    match e {
+        YdbError::Grpc(_) | YdbError::EmptyResponse => true,
+        YdbError::Ydb(ErrWithOperation(operation)) => matches!(
+            operation.status(),
+            StatusCode::Unavailable | StatusCode::Overloaded | StatusCode::Aborted | StatusCode::Undetermined
+                | StatusCode::Timeout | StatusCode::BadSession | StatusCode::SessionExpired | StatusCode::SessionBusy
+        ),
+        _ => false,
+    }
+}
+
+#[test]
+fn alteration_keeps_unset_fields() {
+    let alteration = TopicAlteration { retention_period: Some(Duration::from_secs(60)), ..Default::default() };
+    let req = AlterTopicRequest::from(alteration);
+    assert_eq!(req.set_retention_period.map(|d| d.seconds), Some(60));
+    assert!(req.alter_partitioning_settings.is_none());
+    assert!(req.set_supported_codecs.is_none());
+    let alteration = TopicAlteration { partition_count_limit: Some(10), supported_codecs: Some(vec![Codec::Raw]), ..Default::default() };
+    let req = AlterTopicRequest::from(alteration);
+    assert_eq!(req.alter_partitioning_settings.map(|s| s.set_partition_count_limit), Some(10));
+    assert_eq!(req.set_supported_codecs.map(|c| c.codecs), Some(vec![Codec::Raw as i32]));
+}
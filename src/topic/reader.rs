@@ -0,0 +1,438 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime};
+
+use futures::Stream;
+use futures::channel::mpsc::{unbounded, UnboundedSender};
+use tokio::sync::{mpsc, oneshot};
+use tonic::codec::Streaming;
+
+use crate::auth::Credentials;
+use crate::client::YdbService;
+use crate::error::{YdbError, check_status};
+use crate::generated::ydb::topic::OffsetsRange;
+use crate::generated::ydb::topic::v1::topic_service_client::TopicServiceClient;
+use crate::generated::ydb::topic::stream_read_message::*;
+use crate::generated::ydb::topic::stream_read_message::{from_client::ClientMessage, from_server::ServerMessage};
+use crate::generated::ydb::topic::stream_read_message::commit_offset_request::PartitionCommitOffset;
+use crate::generated::ydb::topic::stream_read_message::init_request::TopicReadSettings;
+use super::codec::{self, Codec};
+use super::{timestamp, system_time, retryable};
+
+/// Settings of [`TopicReader`]
+#[derive(Debug, Clone)]
+pub struct ReaderOptions {
+    /// Name of consumer, that is registered in topic
+    pub consumer: String,
+    /// Name of reader for debug purposes
+    pub reader_name: String,
+    /// Read only these partitions (all partitions if empty)
+    pub partition_ids: Vec<i64>,
+    /// Read only messages written after this time
+    pub read_from: Option<SystemTime>,
+    /// Max size of messages, that server sends before they are consumed from reader (flow control)
+    pub buffer_bytes: i64,
+    /// Delay between attempts to reconnect after transport error
+    pub reconnect_interval: Duration,
+    /// Reader stops with error, if it cannot reconnect during this time
+    pub reconnect_timeout: Duration,
+}
+
+impl Default for ReaderOptions {
+    fn default() -> Self {
+        Self {
+            consumer: String::new(),
+            reader_name: String::new(),
+            partition_ids: Vec::new(),
+            read_from: None,
+            buffer_bytes: 1024 * 1024,
+            reconnect_interval: Duration::from_millis(500),
+            reconnect_timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Message, read from topic
+#[derive(Debug, Clone)]
+pub struct ReadMessage {
+    pub offset: i64,
+    pub seq_no: i64,
+    pub created_at: SystemTime,
+    pub written_at: SystemTime,
+    pub message_group_id: String,
+    /// Decompressed body of message
+    pub data: Vec<u8>,
+}
+
+/// Messages of one partition, written by one producer
+#[derive(Debug)]
+pub struct ReadBatch {
+    pub topic: String,
+    pub partition_id: i64,
+    pub producer_id: String,
+    pub session_meta: HashMap<String, String>,
+    pub messages: Vec<ReadMessage>,
+    commit: CommitHandle,
+    bytes: i64,
+}
+
+impl ReadBatch {
+    /// Returns handle to commit batch later (e.g. from another task)
+    pub fn commit_handle(&self) -> CommitHandle {
+        self.commit.clone()
+    }
+    /// Commits offsets of batch and waits for confirmation of server
+    pub async fn commit(&self) -> Result<(), YdbError> {
+        self.commit.clone().commit().await
+    }
+}
+
+type Reply = oneshot::Sender<Result<(), YdbError>>;
+
+enum Command {
+    Commit { handle: CommitHandle, reply: Reply },
+    Consumed(i64),
+}
+
+/// Handle to commit offsets of read batch. It can be moved to other tasks.
+/// Offsets are committed only after all previous offsets of partition are committed
+#[derive(Debug, Clone)]
+pub struct CommitHandle {
+    commands: mpsc::UnboundedSender<Command>,
+    generation: u64,
+    partition_session_id: i64,
+    offsets: Range<i64>,
+}
+
+impl CommitHandle {
+    pub fn offsets(&self) -> Range<i64> {
+        self.offsets.clone()
+    }
+    /// Commits offsets and waits until server confirms them.
+    /// Fails if partition session was stopped (then messages will be read again by some reader)
+    pub async fn commit(self) -> Result<(), YdbError> {
+        let (reply, committed) = oneshot::channel();
+        let commands = self.commands.clone();
+        commands.send(Command::Commit { handle: self, reply }).map_err(|_| YdbError::SessionClosed)?;
+        committed.await.map_err(|_| YdbError::SessionClosed)?
+    }
+}
+
+/// Stream of batches from topic. Reader works in background task: it starts and stops partition sessions,
+/// requests more data from server when batches are consumed, and reconnects after transport errors.
+/// Uncommitted messages are read again after reconnect. Reader stops, when stream is dropped.
+/// After other errors (e.g. when messages cannot be decoded) stream returns the error and ends
+///
+/// # Examples
+/// ```rust,no_run
+/// # #[tokio::main]
+/// # async fn main() {
+///     use futures::StreamExt;
+///     use ydb_unofficial::topic::ReaderOptions;
+///     let mut conn = ydb_unofficial::YdbConnection::from_env();
+///     let options = ReaderOptions { consumer: "my-consumer".to_owned(), ..Default::default() };
+///     let mut reader = conn.topic().reader(&["events"], options).await.unwrap();
+///     while let Some(batch) = reader.next().await {
+///         let batch = batch.unwrap();
+///         for message in &batch.messages {
+///             println!("{}: {:?}", message.offset, message.data);
+///         }
+///         batch.commit().await.unwrap();
+///     }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct TopicReader {
+    batches: mpsc::UnboundedReceiver<Result<ReadBatch, YdbError>>,
+    commands: mpsc::UnboundedSender<Command>,
+}
+
+impl TopicReader {
+    pub(crate) async fn start<C: Credentials>(service: YdbService<C>, topics: &[&str], options: ReaderOptions) -> Result<Self, YdbError> {
+        let (commands, command_receiver) = mpsc::unbounded_channel();
+        let (batch_sender, batches) = mpsc::unbounded_channel();
+        let mut actor = Actor {
+            client: TopicServiceClient::new(service),
+            topics: topics.iter().map(|t| t.to_string()).collect(),
+            options,
+            generation: 0,
+            sessions: HashMap::new(),
+            commands: commands.clone(),
+            batches: batch_sender,
+        };
+        let connection = actor.connect().await?;
+        tokio::spawn(actor.run(connection, command_receiver));
+        Ok(Self { batches, commands })
+    }
+}
+
+impl Stream for TopicReader {
+    type Item = Result<ReadBatch, YdbError>;
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let polled = self.batches.poll_recv(cx);
+        if let Poll::Ready(Some(Ok(batch))) = &polled {
+            //free space in buffer of server, so it can send more data
+            let _ = self.commands.send(Command::Consumed(batch.bytes));
+        }
+        polled
+    }
+}
+
+/// Partition session, assigned to reader by server
+struct PartitionSession {
+    topic: String,
+    partition_id: i64,
+    /// End of offsets, that was passed to reader (next commit starts from it)
+    read_offset: i64,
+    committed_offset: i64,
+    pending_commits: Vec<(i64, Reply)>,
+}
+
+impl PartitionSession {
+    fn committed(&mut self, offset: i64) {
+        self.committed_offset = offset;
+        let (done, pending) = std::mem::take(&mut self.pending_commits).into_iter().partition(|(end, _)| *end <= offset);
+        self.pending_commits = pending;
+        for (_, reply) in done {
+            let _ = reply.send(Ok(()));
+        }
+    }
+    fn stop(self) {
+        for (_, reply) in self.pending_commits {
+            let _ = reply.send(Err(YdbError::SessionClosed));
+        }
+    }
+}
+
+/// Splits `total` bytes between `count` batches (the last batch takes remainder)
+fn split_bytes(total: i64, count: usize) -> impl Iterator<Item = i64> {
+    let count = count.max(1) as i64;
+    let part = total / count;
+    (0..count).map(move |i| if i == count - 1 { total - part * (count - 1) } else { part })
+}
+
+struct Connection {
+    sender: UnboundedSender<FromClient>,
+    responses: Streaming<FromServer>,
+}
+
+impl Connection {
+    fn send(&self, message: ClientMessage) {
+        //if stream is broken, error will be received from responses
+        let _ = self.sender.unbounded_send(FromClient { client_message: Some(message) });
+    }
+}
+
+struct Actor<C: Credentials> {
+    client: TopicServiceClient<YdbService<C>>,
+    topics: Vec<String>,
+    options: ReaderOptions,
+    /// Incremented on reconnect: partition session ids of previous connection are not valid
+    generation: u64,
+    sessions: HashMap<i64, PartitionSession>,
+    commands: mpsc::UnboundedSender<Command>,
+    batches: mpsc::UnboundedSender<Result<ReadBatch, YdbError>>,
+}
+
+impl<C: Credentials> Actor<C> {
+    async fn connect(&mut self) -> Result<Connection, YdbError> {
+        let (sender, receiver) = unbounded();
+        let topics_read_settings = self.topics.iter().map(|path| TopicReadSettings {
+            path: path.clone(),
+            partition_ids: self.options.partition_ids.clone(),
+            max_lag: None,
+            read_from: self.options.read_from.map(timestamp),
+        }).collect();
+        let init = InitRequest {
+            topics_read_settings,
+            consumer: self.options.consumer.clone(),
+            reader_name: self.options.reader_name.clone(),
+        };
+        let _ = sender.unbounded_send(FromClient { client_message: Some(ClientMessage::InitRequest(init)) });
+        let mut connection = Connection { sender, responses: self.client.stream_read(receiver).await?.into_inner() };
+        let response = connection.responses.message().await?.ok_or(YdbError::EmptyResponse)?;
+        check_status(response.status, response.issues)?;
+        match response.server_message {
+            Some(ServerMessage::InitResponse(init)) => log::debug!("Topic read session started: {}", init.session_id),
+            _ => return Err(YdbError::EmptyResponse),
+        }
+        self.generation += 1;
+        connection.send(ClientMessage::ReadRequest(ReadRequest { bytes_size: self.options.buffer_bytes }));
+        Ok(connection)
+    }
+    async fn run(mut self, mut connection: Connection, mut commands: mpsc::UnboundedReceiver<Command>) {
+        loop {
+            let Err(e) = self.serve(&mut connection, &mut commands).await else {
+                log::debug!("Topic reader stopped");
+                return;
+            };
+            for (_, session) in self.sessions.drain() {
+                session.stop();
+            }
+            if !retryable(&e) {
+                log::error!("Topic reader stopped: {e}");
+                let _ = self.batches.send(Err(e));
+                return;
+            }
+            log::warn!("Topic reader disconnected: {e}");
+            match self.reconnect().await {
+                Ok(restored) => connection = restored,
+                Err(e) => {
+                    let _ = self.batches.send(Err(e));
+                    return;
+                }
+            }
+        }
+    }
+    /// Serves connection until it breaks. Returns `Ok`, when reader is dropped
+    async fn serve(&mut self, connection: &mut Connection, commands: &mut mpsc::UnboundedReceiver<Command>) -> Result<(), YdbError> {
+        loop {
+            tokio::select! {
+                _ = self.batches.closed() => return Ok(()),
+                Some(command) = commands.recv() => match command {
+                    Command::Consumed(bytes) => if bytes > 0 {
+                        connection.send(ClientMessage::ReadRequest(ReadRequest { bytes_size: bytes }));
+                    }
+                    Command::Commit { handle, reply } => self.commit(connection, handle, reply),
+                },
+                response = connection.responses.message() => {
+                    let response = response?.ok_or(YdbError::EmptyResponse)?;
+                    check_status(response.status, response.issues)?;
+                    if let Some(message) = response.server_message {
+                        self.handle(connection, message)?;
+                    }
+                }
+            }
+        }
+    }
+    fn handle(&mut self, connection: &Connection, message: ServerMessage) -> Result<(), YdbError> {
+        match message {
+            ServerMessage::StartPartitionSessionRequest(request) => {
+                let session = request.partition_session.ok_or(YdbError::EmptyResponse)?;
+                log::debug!("Partition session {} started: {} partition {}", session.partition_session_id, session.path, session.partition_id);
+                self.sessions.insert(session.partition_session_id, PartitionSession {
+                    topic: session.path,
+                    partition_id: session.partition_id,
+                    read_offset: request.committed_offset,
+                    committed_offset: request.committed_offset,
+                    pending_commits: Vec::new(),
+                });
+                connection.send(ClientMessage::StartPartitionSessionResponse(StartPartitionSessionResponse {
+                    partition_session_id: session.partition_session_id,
+                    ..Default::default()
+                }));
+            }
+            ServerMessage::StopPartitionSessionRequest(request) => {
+                let id = request.partition_session_id;
+                log::debug!("Partition session {id} stopped (graceful: {})", request.graceful);
+                if let Some(mut session) = self.sessions.remove(&id) {
+                    session.committed(request.committed_offset);
+                    session.stop();
+                }
+                if request.graceful {
+                    connection.send(ClientMessage::StopPartitionSessionResponse(StopPartitionSessionResponse { partition_session_id: id }));
+                }
+            }
+            ServerMessage::CommitOffsetResponse(response) => {
+                for committed in response.partitions_committed_offsets {
+                    if let Some(session) = self.sessions.get_mut(&committed.partition_session_id) {
+                        session.committed(committed.committed_offset);
+                    }
+                }
+            }
+            ServerMessage::ReadResponse(response) => self.read(response)?,
+            _ => {}
+        }
+        Ok(())
+    }
+    fn read(&mut self, response: ReadResponse) -> Result<(), YdbError> {
+        let count = response.partition_data.iter().map(|p| p.batches.len()).sum();
+        let mut bytes = split_bytes(response.bytes_size, count);
+        for partition in response.partition_data {
+            let id = partition.partition_session_id;
+            for batch in partition.batches {
+                let bytes = bytes.next().unwrap_or_default();
+                let Some(session) = self.sessions.get_mut(&id) else {
+                    log::warn!("Topic reader received data of unknown partition session {id}");
+                    let _ = self.commands.send(Command::Consumed(bytes));
+                    continue;
+                };
+                let codec = Codec::from_i32(batch.codec).unwrap_or(Codec::Unspecified);
+                let written_at = system_time(batch.written_at);
+                let messages = batch.message_data.into_iter().map(|m| Ok(ReadMessage {
+                    offset: m.offset,
+                    seq_no: m.seq_no,
+                    created_at: system_time(m.created_at),
+                    written_at,
+                    message_group_id: m.message_group_id,
+                    data: codec::decode(codec, m.data)?,
+                })).collect::<Result<Vec<_>, YdbError>>()?;
+                let Some(last) = messages.last() else {
+                    let _ = self.commands.send(Command::Consumed(bytes));
+                    continue;
+                };
+                let offsets = session.read_offset..last.offset + 1;
+                session.read_offset = offsets.end;
+                let commit = CommitHandle { commands: self.commands.clone(), generation: self.generation, partition_session_id: id, offsets };
+                let batch = ReadBatch {
+                    topic: session.topic.clone(),
+                    partition_id: session.partition_id,
+                    producer_id: batch.producer_id,
+                    session_meta: batch.write_session_meta,
+                    messages,
+                    commit,
+                    bytes,
+                };
+                let _ = self.batches.send(Ok(batch));
+            }
+        }
+        Ok(())
+    }
+    fn commit(&mut self, connection: &Connection, handle: CommitHandle, reply: Reply) {
+        let session = self.sessions.get_mut(&handle.partition_session_id).filter(|_| handle.generation == self.generation);
+        let Some(session) = session else {
+            let _ = reply.send(Err(YdbError::SessionClosed));
+            return;
+        };
+        if handle.offsets.end <= session.committed_offset {
+            let _ = reply.send(Ok(()));
+            return;
+        }
+        session.pending_commits.push((handle.offsets.end, reply));
+        let offsets = vec![OffsetsRange { start: handle.offsets.start, end: handle.offsets.end }];
+        let commit_offsets = vec![PartitionCommitOffset { partition_session_id: handle.partition_session_id, offsets }];
+        connection.send(ClientMessage::CommitOffsetRequest(CommitOffsetRequest { commit_offsets }));
+    }
+    async fn reconnect(&mut self) -> Result<Connection, YdbError> {
+        let deadline = std::time::Instant::now() + self.options.reconnect_timeout;
+        loop {
+            tokio::time::sleep(self.options.reconnect_interval).await;
+            match self.connect().await {
+                Ok(connection) => {
+                    log::debug!("Topic reader reconnected");
+                    return Ok(connection);
+                }
+                Err(e) if !retryable(&e) || std::time::Instant::now() > deadline => {
+                    log::error!("Cannot restore topic reader: {e}");
+                    return Err(e);
+                }
+                Err(e) => log::warn!("Cannot restore topic reader: {e}"),
+            }
+        }
+    }
+}
+
+#[test]
+fn split_response_bytes() {
+    assert_eq!(split_bytes(10, 3).collect::<Vec<_>>(), vec![3, 3, 4]);
+    assert_eq!(split_bytes(10, 1).collect::<Vec<_>>(), vec![10]);
+    assert_eq!(split_bytes(10, 0).sum::<i64>(), 10);
+}
+
+#[test]
+fn decode_error_stops_reader() {
+    let e = codec::decode(Codec::Lzop, vec![1, 2, 3]).unwrap_err();
+    assert!(!retryable(&e));
+}
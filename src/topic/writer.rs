@@ -0,0 +1,448 @@
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime};
+
+use futures::channel::mpsc::{unbounded, UnboundedSender};
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::Instant;
+use tonic::codec::Streaming;
+
+use crate::auth::Credentials;
+use crate::client::YdbService;
+use crate::error::{YdbError, check_status};
+use crate::generated::ydb::topic::v1::topic_service_client::TopicServiceClient;
+use crate::generated::ydb::topic::stream_write_message::{FromClient, FromServer, InitRequest, WriteRequest, WriteResponse};
+use crate::generated::ydb::topic::stream_write_message::{init_request, from_client::ClientMessage, from_server::ServerMessage};
+use crate::generated::ydb::topic::stream_write_message::write_request::MessageData;
+use crate::generated::ydb::topic::stream_write_message::write_response::write_ack::MessageWriteStatus;
+use super::codec::{self, Codec};
+use super::{timestamp, retryable};
+
+/// Settings of [`TopicWriter`]
+#[derive(Debug, Clone)]
+pub struct WriterOptions {
+    /// Id of producer for deduplication: server keeps last seq_no of each producer and skips messages with lower seq_no
+    pub producer_id: String,
+    /// Messages of one group are written to the same partition. Producer id is used as group if `None`
+    pub message_group_id: Option<String>,
+    /// Writes all messages to this partition (instead of partition of message group)
+    pub partition_id: Option<i64>,
+    /// Codec to compress messages. Gzip and zstd need features `gzip` and `zstd`
+    pub codec: Codec,
+    /// Max count of messages in one write request
+    pub batch_size: usize,
+    /// Max size of compressed messages in one write request
+    pub batch_bytes: usize,
+    /// Messages are sent when batch is full or after this delay
+    pub flush_interval: Duration,
+    /// Max count of messages waiting to be sent. [`TopicWriter::write`] waits, when queue is full
+    pub queue_size: usize,
+    /// Delay between attempts to reconnect after transport error
+    pub reconnect_interval: Duration,
+    /// Writer stops, if it cannot reconnect during this time
+    pub reconnect_timeout: Duration,
+    /// Metadata of write session, that is passed to readers
+    pub session_meta: HashMap<String, String>,
+}
+
+impl Default for WriterOptions {
+    fn default() -> Self {
+        Self {
+            producer_id: format!("{:032x}", rand::random::<u128>()),
+            message_group_id: None,
+            partition_id: None,
+            codec: Codec::Raw,
+            batch_size: 1000,
+            batch_bytes: 1024 * 1024,
+            flush_interval: Duration::from_millis(10),
+            queue_size: 10_000,
+            reconnect_interval: Duration::from_millis(500),
+            reconnect_timeout: Duration::from_secs(60),
+            session_meta: HashMap::new(),
+        }
+    }
+}
+
+/// Message to write into topic
+#[derive(Debug, Clone)]
+pub struct Message {
+    pub data: Vec<u8>,
+    /// Sequence number for deduplication. Writer assigns next number if `None`.
+    /// Don't mix messages with and without seq_no in one writer
+    pub seq_no: Option<i64>,
+    pub created_at: SystemTime,
+}
+
+impl Message {
+    pub fn new(data: impl Into<Vec<u8>>) -> Self {
+        Self { data: data.into(), seq_no: None, created_at: SystemTime::now() }
+    }
+    pub fn with_seq_no(self, seq_no: i64) -> Self {
+        Self { seq_no: Some(seq_no), ..self }
+    }
+}
+
+impl From<Vec<u8>> for Message {
+    fn from(value: Vec<u8>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<String> for Message {
+    fn from(value: String) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<&str> for Message {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+/// Acknowledgement of written message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteAck {
+    Written { seq_no: i64, partition_id: i64, offset: i64 },
+    /// Message was written before (e.g. by previous session of producer), so it is skipped
+    Skipped { seq_no: i64 },
+}
+
+type Reply = oneshot::Sender<Result<WriteAck, YdbError>>;
+
+/// Future of message acknowledgement. Resolves, when server confirms that message is written
+#[derive(Debug)]
+pub struct AckFuture(oneshot::Receiver<Result<WriteAck, YdbError>>);
+
+impl Future for AckFuture {
+    type Output = Result<WriteAck, YdbError>;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(cx).map(|ack| ack.unwrap_or(Err(YdbError::SessionClosed)))
+    }
+}
+
+struct Pending {
+    seq_no: Option<i64>,
+    created_at: SystemTime,
+    data: Vec<u8>,
+    uncompressed_size: i64,
+    reply: Reply,
+}
+
+enum Command {
+    Write(Pending),
+    Flush(oneshot::Sender<()>),
+}
+
+/// Handle of topic writer, that works in background task.
+/// Writer batches messages, reconnects after transport errors and resends messages, that are not acknowledged.
+/// It stops after all handles are dropped and all messages are acknowledged
+///
+/// # Examples
+/// ```rust,no_run
+/// # #[tokio::main]
+/// # async fn main() {
+///     use ydb_unofficial::topic::WriterOptions;
+///     let mut conn = ydb_unofficial::YdbConnection::from_env();
+///     let options = WriterOptions { producer_id: "producer-1".to_owned(), ..Default::default() };
+///     let writer = conn.topic().writer("events", options).await.unwrap();
+///     let ack = writer.write("hello").await.unwrap();
+///     writer.write("world").await.unwrap();
+///     println!("{:?}", ack.await.unwrap());
+///     writer.close().await.unwrap();
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TopicWriter {
+    commands: mpsc::Sender<Command>,
+    codec: Codec,
+    last_seq_no: i64,
+}
+
+impl TopicWriter {
+    pub(crate) async fn start<C: Credentials>(service: YdbService<C>, path: &str, options: WriterOptions) -> Result<Self, YdbError> {
+        let codec = options.codec;
+        let (commands, receiver) = mpsc::channel(options.queue_size.max(1));
+        let mut actor = Actor {
+            client: TopicServiceClient::new(service),
+            path: path.to_owned(),
+            options,
+            last_seq_no: 0,
+            buffer: VecDeque::new(),
+            buffer_bytes: 0,
+            inflight: VecDeque::new(),
+            flushes: Vec::new(),
+        };
+        let connection = actor.connect().await?;
+        let last_seq_no = actor.last_seq_no;
+        log::debug!("Topic writer started for {path}, last seq_no: {last_seq_no}");
+        tokio::spawn(actor.run(connection, receiver));
+        Ok(Self { commands, codec, last_seq_no })
+    }
+    /// Last seq_no of producer, that was written before start of writer
+    pub fn last_seq_no(&self) -> i64 {
+        self.last_seq_no
+    }
+    /// Compresses message and puts it to queue. Returns future, that resolves when message is acknowledged
+    pub async fn write(&self, message: impl Into<Message>) -> Result<AckFuture, YdbError> {
+        let Message { data, seq_no, created_at } = message.into();
+        let uncompressed_size = data.len() as i64;
+        let data = codec::encode(self.codec, data)?;
+        let (reply, ack) = oneshot::channel();
+        let pending = Pending { seq_no, created_at, data, uncompressed_size, reply };
+        self.commands.send(Command::Write(pending)).await.map_err(|_| YdbError::SessionClosed)?;
+        Ok(AckFuture(ack))
+    }
+    /// Writes message and waits for its acknowledgement
+    pub async fn write_and_wait(&self, message: impl Into<Message>) -> Result<WriteAck, YdbError> {
+        self.write(message).await?.await
+    }
+    /// Sends queued messages immediately and waits until all of them are acknowledged
+    pub async fn flush(&self) -> Result<(), YdbError> {
+        let (sender, flushed) = oneshot::channel();
+        self.commands.send(Command::Flush(sender)).await.map_err(|_| YdbError::SessionClosed)?;
+        flushed.await.map_err(|_| YdbError::SessionClosed)
+    }
+    /// Flushes messages and drops handle. Writer stops, when all handles are closed or dropped
+    pub async fn close(self) -> Result<(), YdbError> {
+        self.flush().await
+    }
+}
+
+struct Queued {
+    message: MessageData,
+    enqueued: Instant,
+    reply: Reply,
+}
+
+/// Returns sent messages (in order) to front of buffer. Messages with seq_no up to `last_seq_no` are already written,
+/// so they are returned separately to acknowledge them
+fn requeue(inflight: &mut VecDeque<Queued>, buffer: &mut VecDeque<Queued>, last_seq_no: i64) -> Vec<Queued> {
+    let mut written = Vec::new();
+    while let Some(queued) = inflight.pop_back() {
+        if queued.message.seq_no <= last_seq_no {
+            written.push(queued);
+        } else {
+            buffer.push_front(queued);
+        }
+    }
+    written
+}
+
+struct Connection {
+    sender: UnboundedSender<FromClient>,
+    responses: Streaming<FromServer>,
+}
+
+impl Connection {
+    fn send(&self, message: ClientMessage) {
+        //if stream is broken, error will be received from responses
+        let _ = self.sender.unbounded_send(FromClient { client_message: Some(message) });
+    }
+}
+
+struct Actor<C: Credentials> {
+    client: TopicServiceClient<YdbService<C>>,
+    path: String,
+    options: WriterOptions,
+    last_seq_no: i64,
+    buffer: VecDeque<Queued>,
+    buffer_bytes: usize,
+    inflight: VecDeque<Queued>,
+    flushes: Vec<oneshot::Sender<()>>,
+}
+
+impl<C: Credentials> Actor<C> {
+    /// Starts write session and resumes from last seq_no, acknowledged by server
+    async fn connect(&mut self) -> Result<Connection, YdbError> {
+        let (sender, receiver) = unbounded();
+        let partitioning = match self.options.partition_id {
+            Some(id) => init_request::Partitioning::PartitionId(id),
+            None => {
+                let group = self.options.message_group_id.as_ref().unwrap_or(&self.options.producer_id);
+                init_request::Partitioning::MessageGroupId(group.clone())
+            }
+        };
+        let init = InitRequest {
+            path: self.path.clone(),
+            producer_id: self.options.producer_id.clone(),
+            write_session_meta: self.options.session_meta.clone(),
+            get_last_seq_no: true,
+            partitioning: Some(partitioning),
+        };
+        let _ = sender.unbounded_send(FromClient { client_message: Some(ClientMessage::InitRequest(init)) });
+        let mut responses = self.client.stream_write(receiver).await?.into_inner();
+        let response = responses.message().await?.ok_or(YdbError::EmptyResponse)?;
+        check_status(response.status, response.issues)?;
+        let init = match response.server_message {
+            Some(ServerMessage::InitResponse(init)) => init,
+            _ => return Err(YdbError::EmptyResponse),
+        };
+        let codec = self.options.codec;
+        if let Some(supported) = init.supported_codecs.filter(|s| !s.codecs.is_empty()) {
+            if !supported.codecs.contains(&(codec as i32)) {
+                return Err(YdbError::Codec(format!("{} is not supported by topic {}", codec.as_str_name(), self.path)));
+            }
+        }
+        for queued in requeue(&mut self.inflight, &mut self.buffer, init.last_seq_no) {
+            let _ = queued.reply.send(Ok(WriteAck::Skipped { seq_no: queued.message.seq_no }));
+        }
+        self.buffer_bytes = self.buffer.iter().map(|q| q.message.data.len()).sum();
+        self.last_seq_no = self.last_seq_no.max(init.last_seq_no);
+        Ok(Connection { sender, responses })
+    }
+    async fn run(mut self, mut connection: Connection, mut commands: mpsc::Receiver<Command>) {
+        loop {
+            match self.serve(&mut connection, &mut commands).await {
+                Ok(()) => {
+                    log::debug!("Topic writer for {} stopped", self.path);
+                    return;
+                }
+                Err(e) => {
+                    log::warn!("Topic writer for {} disconnected: {e}", self.path);
+                    if let Some(restored) = self.reconnect().await {
+                        connection = restored;
+                    } else {
+                        self.fail_all();
+                        return;
+                    }
+                }
+            }
+        }
+    }
+    /// Serves connection until it breaks. Returns `Ok` when all handles dropped and all messages acknowledged
+    async fn serve(&mut self, connection: &mut Connection, commands: &mut mpsc::Receiver<Command>) -> Result<(), YdbError> {
+        let mut closed = false;
+        loop {
+            while self.buffer.len() >= self.options.batch_size.max(1) || self.buffer_bytes >= self.options.batch_bytes.max(1) {
+                self.send_batch(connection);
+            }
+            if closed && self.buffer.is_empty() && self.inflight.is_empty() {
+                return Ok(());
+            }
+            let deadline = self.buffer.front().map(|q| q.enqueued + self.options.flush_interval);
+            //commands are not received while queue is full, so writers wait for acknowledgements
+            let full = self.buffer.len() + self.inflight.len() >= self.options.queue_size.max(1);
+            tokio::select! {
+                command = commands.recv(), if !closed && !full => match command {
+                    Some(Command::Write(pending)) => self.enqueue(pending),
+                    Some(Command::Flush(notify)) => {
+                        self.flushes.push(notify);
+                        self.send_all(connection);
+                        self.notify_flushed();
+                    }
+                    None => {
+                        closed = true;
+                        self.send_all(connection);
+                    }
+                },
+                _ = tokio::time::sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                    self.send_all(connection);
+                }
+                response = connection.responses.message() => {
+                    let response = response?.ok_or(YdbError::EmptyResponse)?;
+                    check_status(response.status, response.issues)?;
+                    if let Some(ServerMessage::WriteResponse(response)) = response.server_message {
+                        self.ack(response);
+                        self.notify_flushed();
+                    }
+                }
+            }
+        }
+    }
+    fn enqueue(&mut self, pending: Pending) {
+        let Pending { seq_no, created_at, data, uncompressed_size, reply } = pending;
+        let seq_no = seq_no.unwrap_or(self.last_seq_no + 1);
+        self.last_seq_no = seq_no;
+        self.buffer_bytes += data.len();
+        let message = MessageData { seq_no, created_at: Some(timestamp(created_at)), data, uncompressed_size, partitioning: None };
+        self.buffer.push_back(Queued { message, enqueued: Instant::now(), reply });
+    }
+    fn send_batch(&mut self, connection: &Connection) {
+        let mut messages = Vec::new();
+        let mut bytes = 0;
+        while let Some(queued) = self.buffer.front() {
+            let size = queued.message.data.len();
+            if !messages.is_empty() && (messages.len() >= self.options.batch_size || bytes + size > self.options.batch_bytes) {
+                break;
+            }
+            let queued = self.buffer.pop_front().unwrap();
+            bytes += size;
+            messages.push(queued.message.clone());
+            self.inflight.push_back(queued);
+        }
+        self.buffer_bytes -= bytes;
+        if !messages.is_empty() {
+            connection.send(ClientMessage::WriteRequest(WriteRequest { messages, codec: self.options.codec as i32 }));
+        }
+    }
+    fn send_all(&mut self, connection: &Connection) {
+        while !self.buffer.is_empty() {
+            self.send_batch(connection);
+        }
+    }
+    fn ack(&mut self, response: WriteResponse) {
+        for ack in response.acks {
+            let Some(position) = self.inflight.iter().position(|q| q.message.seq_no == ack.seq_no) else {
+                log::warn!("Topic writer for {} received ack for unknown seq_no {}", self.path, ack.seq_no);
+                continue;
+            };
+            let queued = self.inflight.remove(position).unwrap();
+            let result = match ack.message_write_status {
+                Some(MessageWriteStatus::Written(written)) => {
+                    WriteAck::Written { seq_no: ack.seq_no, partition_id: response.partition_id, offset: written.offset }
+                }
+                _ => WriteAck::Skipped { seq_no: ack.seq_no },
+            };
+            let _ = queued.reply.send(Ok(result));
+        }
+    }
+    fn notify_flushed(&mut self) {
+        if self.buffer.is_empty() && self.inflight.is_empty() {
+            for notify in self.flushes.drain(..) {
+                let _ = notify.send(());
+            }
+        }
+    }
+    /// Tries to reconnect until timeout exceeded. Returns `None` if writer cannot be restored
+    async fn reconnect(&mut self) -> Option<Connection> {
+        let deadline = std::time::Instant::now() + self.options.reconnect_timeout;
+        loop {
+            tokio::time::sleep(self.options.reconnect_interval).await;
+            match self.connect().await {
+                Ok(connection) => {
+                    log::debug!("Topic writer for {} reconnected, last seq_no: {}", self.path, self.last_seq_no);
+                    return Some(connection);
+                }
+                Err(e) if !retryable(&e) || std::time::Instant::now() > deadline => {
+                    log::error!("Cannot restore topic writer for {}: {e}", self.path);
+                    return None;
+                }
+                Err(e) => log::warn!("Cannot restore topic writer for {}: {e}", self.path),
+            }
+        }
+    }
+    fn fail_all(&mut self) {
+        for queued in self.inflight.drain(..).chain(self.buffer.drain(..)) {
+            let _ = queued.reply.send(Err(YdbError::SessionClosed));
+        }
+    }
+}
+
+#[test]
+fn requeue_after_reconnect() {
+    let queued = |seq_no| {
+        let message = MessageData { seq_no, ..Default::default() };
+        Queued { message, enqueued: Instant::now(), reply: oneshot::channel().0 }
+    };
+    let mut inflight: VecDeque<_> = (1..=4).map(queued).collect();
+    let mut buffer: VecDeque<_> = (5..=6).map(queued).collect();
+    let written = requeue(&mut inflight, &mut buffer, 2);
+    let mut written: Vec<_> = written.iter().map(|q| q.message.seq_no).collect();
+    written.sort();
+    assert_eq!(written, vec![1, 2]);
+    assert!(inflight.is_empty());
+    assert_eq!(buffer.iter().map(|q| q.message.seq_no).collect::<Vec<_>>(), vec![3, 4, 5, 6]);
+}